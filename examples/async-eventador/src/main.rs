@@ -17,7 +17,8 @@ async fn main() -> anyhow::Result<()> {
             let event = subscriber
                 .next()
                 .await
-                .expect("stream of subscribed events closed");
+                .expect("stream of subscribed events closed")
+                .expect("subscriber was evicted from the event-bus");
             println!("Received event: {}", *event);
 
             assert_eq!(event_ctr + 1, *event);
@@ -11,7 +11,9 @@ fn main() -> anyhow::Result<()> {
         while event_ctr < NUM_EVENTS {
             // std::thread::sleep(std::time::Duration::from_micros(10));
 
-            let event = subscriber.recv();
+            let event = subscriber
+                .recv()
+                .expect("subscriber was evicted from the event-bus");
             println!("Received event: {}", *event);
 
             assert_eq!(event_ctr + 1, *event);
@@ -25,7 +27,7 @@ fn main() -> anyhow::Result<()> {
         while i <= NUM_EVENTS {
             // std::thread::sleep(std::time::Duration::from_micros(10));
 
-            if i % 2 == 0 {
+            if i.is_multiple_of(2) {
                 publisher.send(i);
             } else {
                 eventbus.publish(i);
@@ -0,0 +1,302 @@
+pub(crate) mod publisher;
+pub(crate) mod subscriber;
+
+pub use publisher::BridgePublisher;
+pub use subscriber::BridgeSubscriber;
+
+use crate::error::Error;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long to wait for a TCP connection to be established.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Frames larger than this are rejected rather than allocated for.
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Sent by a [`BridgePublisher`] when it connects, followed by the id of its session.
+const HELLO: u8 = 1;
+
+/// Sent by a [`BridgeSubscriber`] in reply to a hello, followed by the sequence of the last event
+/// it republished from that session.
+const RESUME: u8 = 2;
+
+/// Followed by the sequence of the event, the length of its type name, the type name, and the
+/// encoded event.
+const EVENT: u8 = 3;
+
+/// Sent by a [`BridgeSubscriber`], followed by the sequence of the last event it republished.
+const ACK: u8 = 4;
+
+/// The address that a bridge listens on or connects to.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BridgeAddr {
+    /// A TCP socket address, such as `"127.0.0.1:9000"`. Host names are resolved on every
+    /// connection attempt.
+    Tcp(String),
+
+    /// The path of a Unix domain socket.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl BridgeAddr {
+    /// Creates a TCP address.
+    pub fn tcp(addr: impl Into<String>) -> Self {
+        BridgeAddr::Tcp(addr.into())
+    }
+
+    /// Creates a Unix domain socket address.
+    #[cfg(unix)]
+    pub fn unix(path: impl Into<PathBuf>) -> Self {
+        BridgeAddr::Unix(path.into())
+    }
+
+    fn connect(&self) -> std::io::Result<Connection> {
+        match self {
+            BridgeAddr::Tcp(addr) => {
+                let mut last_err = None;
+
+                for addr in addr.to_socket_addrs()? {
+                    match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+                        Ok(stream) => {
+                            stream.set_nodelay(true)?;
+                            return Ok(Connection::Tcp(stream));
+                        }
+                        Err(err) => last_err = Some(err),
+                    }
+                }
+
+                Err(last_err.unwrap_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "address did not resolve")
+                }))
+            }
+
+            #[cfg(unix)]
+            BridgeAddr::Unix(path) => UnixStream::connect(path).map(Connection::Unix),
+        }
+    }
+
+    fn bind(&self) -> std::io::Result<Listener> {
+        match self {
+            BridgeAddr::Tcp(addr) => TcpListener::bind(addr.as_str()).map(Listener::Tcp),
+
+            #[cfg(unix)]
+            BridgeAddr::Unix(path) => UnixListener::bind(path).map(Listener::Unix),
+        }
+    }
+}
+
+impl std::fmt::Display for BridgeAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BridgeAddr::Tcp(addr) => write!(f, "tcp://{}", addr),
+
+            #[cfg(unix)]
+            BridgeAddr::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
+enum Connection {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Connection {
+    fn try_clone(&self) -> std::io::Result<Self> {
+        match self {
+            Connection::Tcp(stream) => stream.try_clone().map(Connection::Tcp),
+
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.try_clone().map(Connection::Unix),
+        }
+    }
+
+    /// Closes both directions of the connection, which unblocks any thread reading from or
+    /// writing to it.
+    fn shutdown(&self) {
+        let _ = match self {
+            Connection::Tcp(stream) => stream.shutdown(Shutdown::Both),
+
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.shutdown(Shutdown::Both),
+        };
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Connection::Tcp(stream) => stream.read(buf),
+
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Connection::Tcp(stream) => stream.write(buf),
+
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Connection::Tcp(stream) => stream.flush(),
+
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    fn accept(&self) -> std::io::Result<Connection> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(Connection::Tcp(stream))
+            }
+
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener
+                .accept()
+                .map(|(stream, _)| Connection::Unix(stream)),
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<BridgeAddr> {
+        match self {
+            Listener::Tcp(listener) => listener
+                .local_addr()
+                .map(|addr| BridgeAddr::Tcp(addr.to_string())),
+
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener.local_addr().and_then(|addr| {
+                addr.as_pathname()
+                    .map(|path| BridgeAddr::Unix(path.to_path_buf()))
+                    .ok_or_else(|| std::io::Error::other("unnamed unix socket"))
+            }),
+        }
+    }
+}
+
+fn io_error(err: std::io::Error) -> Error {
+    Error::Bridge(err.to_string())
+}
+
+/// Checks whether an event of the type `name` that encodes to `payload` fits in a frame.
+fn fits_event_frame(name: &str, payload: &[u8]) -> bool {
+    name.len() <= u16::MAX as usize && 1 + 8 + 2 + name.len() + payload.len() <= MAX_FRAME_LEN
+}
+
+/// Writes a frame, laid out as the length of its body, its kind, and its body.
+///
+/// Nothing is written if the frame is larger than the other side accepts.
+fn write_frame(writer: &mut impl Write, kind: u8, parts: &[&[u8]]) -> std::io::Result<()> {
+    let len: usize = 1 + parts.iter().map(|part| part.len()).sum::<usize>();
+    if len > MAX_FRAME_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "bridge frame is too large",
+        ));
+    }
+
+    writer.write_all(&(len as u32).to_le_bytes())?;
+    writer.write_all(&[kind])?;
+
+    for part in parts {
+        writer.write_all(part)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the next frame, and returns its kind and body.
+fn read_frame(reader: &mut impl Read) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+
+    let len = u32::from_le_bytes(len) as usize;
+    if len == 0 || len > MAX_FRAME_LEN {
+        return Err(invalid_frame());
+    }
+
+    let mut frame = vec![0; len];
+    reader.read_exact(&mut frame)?;
+
+    let body = frame.split_off(1);
+    Ok((frame[0], body))
+}
+
+/// Reads a frame of the given kind whose body is a single sequence.
+fn read_sequence_frame(reader: &mut impl Read, kind: u8) -> std::io::Result<u64> {
+    match read_frame(reader)? {
+        (frame_kind, body) if frame_kind == kind => decode_sequence(&body),
+        _ => Err(invalid_frame()),
+    }
+}
+
+fn decode_sequence(body: &[u8]) -> std::io::Result<u64> {
+    match body.get(..8) {
+        Some(bytes) => {
+            let mut sequence = [0; 8];
+            sequence.copy_from_slice(bytes);
+            Ok(u64::from_le_bytes(sequence))
+        }
+        None => Err(invalid_frame()),
+    }
+}
+
+fn invalid_frame() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid bridge frame")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bridge::*;
+
+    #[test]
+    fn round_trips_frames() {
+        let mut bytes = Vec::new();
+        write_frame(&mut bytes, ACK, &[&7_u64.to_le_bytes()]).unwrap();
+        write_frame(&mut bytes, EVENT, &[&[1, 2], &[3]]).unwrap();
+
+        let mut reader = bytes.as_slice();
+        assert_eq!(7, read_sequence_frame(&mut reader, ACK).unwrap());
+        assert_eq!((EVENT, vec![1, 2, 3]), read_frame(&mut reader).unwrap());
+        assert!(read_frame(&mut reader).is_err());
+
+        let oversized = ((MAX_FRAME_LEN + 1) as u32).to_le_bytes();
+        assert!(read_frame(&mut &oversized[..]).is_err());
+
+        // Frames the other side would reject are never written
+        let payload = vec![0; MAX_FRAME_LEN];
+        let mut bytes = Vec::new();
+        assert!(write_frame(&mut bytes, EVENT, &[&payload]).is_err());
+        assert!(bytes.is_empty());
+
+        assert!(fits_event_frame("order", &payload[..MAX_FRAME_LEN - 16]));
+        assert!(!fits_event_frame("order", &payload));
+        assert!(!fits_event_frame(&"n".repeat(u16::MAX as usize + 1), &[]));
+    }
+}
@@ -0,0 +1,325 @@
+use crate::bridge::{
+    fits_event_frame, io_error, read_sequence_frame, write_frame, BridgeAddr, Connection, ACK,
+    EVENT, HELLO, RESUME,
+};
+use crate::error::Error;
+use crate::registry::TypeRegistry;
+use crate::ring_buffer::RingBuffer;
+use crate::sequence::gating_sequence::GatingSequence;
+use crate::waiter::Alerter;
+use crate::Eventador;
+use crossbeam::sync::{Parker, Unparker};
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MIN_RETRY: Duration = Duration::from_millis(50);
+const MAX_RETRY: Duration = Duration::from_secs(5);
+
+static NEXT_SESSION: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies a bridge publisher to its subscriber across reconnections. The id only needs to
+/// differ from those of other publishers, including ones from earlier runs of the same process.
+fn session_id() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as u64);
+
+    nanos ^ ((std::process::id() as u64) << 32) ^ NEXT_SESSION.fetch_add(1, Ordering::Relaxed)
+}
+
+struct PublisherState {
+    closed: AtomicBool,
+    connected: AtomicBool,
+    acked: AtomicU64,
+    connection: Mutex<Option<Connection>>,
+}
+
+/// Streams the events of a local event-bus to a [`BridgeSubscriber`](crate::BridgeSubscriber)
+/// over a TCP or Unix domain socket.
+///
+/// Every event published after the bridge was created is sent, if its type is registered in the
+/// bridge's [`TypeRegistry`]. Events are sent on a dedicated thread, which reconnects whenever
+/// the connection is lost and resumes from the first event that the remote side had not yet
+/// republished.
+///
+/// The bridge gates the local event-bus until the remote side acknowledges each event, so that an
+/// event can be resent after a reconnection. A remote event-bus that waits on its own subscribers
+/// therefore holds back local publishers, according to the local wait-strategy. A bridge that is
+/// evicted for lagging behind stops sending events and no longer connects.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let registry = TypeRegistry::new().register::<Order>("order");
+/// let eventbus = Eventador::new(1024)?;
+///
+/// let bridge = BridgePublisher::connect(&eventbus, registry, BridgeAddr::tcp("10.0.0.2:9000"))?;
+/// eventbus.publish(order);
+/// ```
+///
+pub struct BridgePublisher {
+    state: Arc<PublisherState>,
+    unparker: Unparker,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BridgePublisher {
+    /// Starts bridging the events of `eventbus` to the subscriber listening at `addr`.
+    ///
+    /// This returns without waiting for the connection, which is retried with an exponential
+    /// backoff until it succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Bridge`] if the bridge's thread could not be started.
+    ///
+    pub fn connect(
+        eventbus: &Eventador,
+        registry: TypeRegistry,
+        addr: BridgeAddr,
+    ) -> Result<Self, Error> {
+        let ring = &eventbus.ring;
+
+        let next = ring.sequencer().get() + 1;
+        let sequence = ring.sequencer().register_gating_sequence(next);
+
+        let state = Arc::new(PublisherState {
+            closed: AtomicBool::new(false),
+            connected: AtomicBool::new(false),
+            acked: AtomicU64::new(next - 1),
+            connection: Mutex::new(None),
+        });
+
+        let parker = Parker::new();
+        let unparker = parker.unparker().clone();
+
+        let mut writer = BridgeWriter {
+            ring: Arc::downgrade(ring),
+            registry,
+            addr,
+            sequence,
+            state: state.clone(),
+            parker,
+            session: session_id(),
+            next,
+            last_sent: next - 1,
+        };
+
+        let thread = std::thread::Builder::new()
+            .name(String::from("eventador-bridge"))
+            .spawn(move || writer.run())
+            .map_err(io_error)?;
+
+        Ok(Self {
+            state,
+            unparker,
+            thread: Some(thread),
+        })
+    }
+
+    /// Returns whether the bridge is currently connected to its subscriber.
+    pub fn is_connected(&self) -> bool {
+        self.state.connected.load(Ordering::Acquire)
+    }
+
+    /// Get the sequence of the last event that the remote side acknowledged republishing.
+    pub fn acknowledged(&self) -> u64 {
+        self.state.acked.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for BridgePublisher {
+    fn drop(&mut self) {
+        self.state.closed.store(true, Ordering::Release);
+
+        if let Some(connection) = self.state.connection.lock().unwrap().take() {
+            connection.shutdown();
+        }
+
+        self.unparker.unpark();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+struct BridgeWriter {
+    ring: Weak<RingBuffer>,
+    registry: TypeRegistry,
+    addr: BridgeAddr,
+    sequence: Arc<GatingSequence>,
+    state: Arc<PublisherState>,
+    parker: Parker,
+    session: u64,
+
+    /// The sequence of the next event to send.
+    next: u64,
+
+    /// The sequence of the last event that was sent.
+    last_sent: u64,
+}
+
+impl BridgeWriter {
+    fn run(&mut self) {
+        let mut retry = MIN_RETRY;
+
+        while !self.is_closed() {
+            if let Ok(connection) = self.addr.connect() {
+                retry = MIN_RETRY;
+
+                let _ = self.stream(connection);
+                self.state.connected.store(false, Ordering::Release);
+
+                if let Some(connection) = self.state.connection.lock().unwrap().take() {
+                    connection.shutdown();
+                }
+            }
+
+            if self.is_closed() {
+                break;
+            }
+
+            self.parker.park_timeout(retry);
+            retry = std::cmp::min(retry * 2, MAX_RETRY);
+        }
+
+        // Stop gating publishers once the bridge is gone
+        if let Some(ring) = self.ring.upgrade() {
+            ring.sequencer().deregister_gating_sequence(&self.sequence);
+        }
+    }
+
+    /// The bridge stops once it is dropped or evicted from the event-bus, or once every handle to
+    /// the event-bus is dropped.
+    fn is_closed(&self) -> bool {
+        self.state.closed.load(Ordering::Acquire)
+            || self.sequence.is_evicted()
+            || self.ring.strong_count() == 0
+    }
+
+    fn stream(&mut self, connection: Connection) -> std::io::Result<()> {
+        let mut reader = connection.try_clone()?;
+        self.state
+            .connection
+            .lock()
+            .unwrap()
+            .replace(connection.try_clone()?);
+
+        // The bridge may have been dropped before the connection could be shut down
+        if self.is_closed() {
+            return Ok(());
+        }
+
+        let mut writer = BufWriter::new(connection);
+        write_frame(&mut writer, HELLO, &[&self.session.to_le_bytes()])?;
+        writer.flush()?;
+
+        // Resend every event after the last one the subscriber republished, which are still in
+        // the ring since the bridge gates on the last acknowledged event
+        let resumed = read_sequence_frame(&mut reader, RESUME)?;
+        let acked = self.state.acked.fetch_max(resumed, Ordering::AcqRel);
+        self.next = std::cmp::max(std::cmp::max(acked, resumed) + 1, self.sequence.get());
+        self.state.connected.store(true, Ordering::Release);
+
+        let state = self.state.clone();
+        let unparker = self.parker.unparker().clone();
+        std::thread::Builder::new()
+            .name(String::from("eventador-bridge-ack"))
+            .spawn(move || {
+                while let Ok(acked) = read_sequence_frame(&mut reader, ACK) {
+                    state.acked.fetch_max(acked, Ordering::AcqRel);
+                    unparker.unpark();
+                }
+            })?;
+
+        loop {
+            let ring = match self.ring.upgrade() {
+                Some(ring) if !self.is_closed() => ring,
+                _ => return Ok(()),
+            };
+
+            self.release();
+
+            let envelope = ring
+                .get_envelope(self.next)
+                .expect("ring buffer was not pre-populated with empty event envelopes");
+
+            let envelope_sequence = envelope.sequence();
+            if self.next == envelope_sequence {
+                let registry = &self.registry;
+                let encoded = envelope
+                    .with_event(|event| {
+                        registry.encode_event(event).map(|encoded| {
+                            encoded.map(|(name, payload)| (name.to_string(), payload))
+                        })
+                    })
+                    .flatten();
+
+                // The event was overwritten while it was being encoded
+                if envelope.sequence() != self.next {
+                    continue;
+                }
+
+                // Events that fail to encode, or are too large for a frame, can never be sent, so
+                // they are skipped
+                if let Some(Ok((name, payload))) = encoded {
+                    if !fits_event_frame(&name, &payload) {
+                        self.next += 1;
+                        continue;
+                    }
+
+                    write_frame(
+                        &mut writer,
+                        EVENT,
+                        &[
+                            &self.next.to_le_bytes(),
+                            &(name.len() as u16).to_le_bytes(),
+                            name.as_bytes(),
+                            &payload,
+                        ],
+                    )?;
+
+                    self.last_sent = self.next;
+                }
+
+                self.next += 1;
+            } else if self.next < envelope_sequence {
+                // Publisher has overwritten events before they could be sent, which can only
+                // happen with lossy wait-strategies
+                ring.record_overwritten(self.sequence.id(), self.next, envelope_sequence);
+                self.next = envelope_sequence;
+            } else {
+                writer.flush()?;
+                drop(ring);
+
+                let alerter = Alerter::unparker(&self.parker);
+                self.sequence
+                    .waiter()
+                    .wait(&envelope, self.next, alerter, || {
+                        self.parker.park_timeout(Duration::from_millis(100))
+                    });
+            }
+        }
+    }
+
+    /// Lets publishers overwrite every event that no longer needs to be kept for a resend.
+    fn release(&self) {
+        let acked = self.state.acked.load(Ordering::Acquire);
+
+        // Events after the last one sent were not registered, so they never need to be resent
+        let released = if acked >= self.last_sent {
+            self.next
+        } else {
+            acked + 1
+        };
+
+        if released > self.sequence.get() {
+            self.sequence.set(released);
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use crate::bridge::{
+    decode_sequence, invalid_frame, io_error, read_frame, write_frame, BridgeAddr, Connection,
+    Listener, ACK, EVENT, HELLO, RESUME,
+};
+use crate::error::Error;
+use crate::event::Metadata;
+use crate::registry::TypeRegistry;
+use crate::ring_buffer::RingBuffer;
+use crate::{Eventador, WaitStrategy};
+use std::collections::HashMap;
+use std::io::{BufReader, BufWriter, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// The most events that are republished before they are acknowledged, while more are buffered.
+const ACK_INTERVAL: u64 = 64;
+
+/// How long republishing waits on the event-bus at a time, before checking whether the
+/// subscriber was closed.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a publisher's session is remembered once none of its connections are open.
+const SESSION_EXPIRY: Duration = Duration::from_secs(60);
+
+struct SubscriberState {
+    closed: AtomicBool,
+    next_connection_id: AtomicU64,
+    sessions: Mutex<HashMap<u64, Session>>,
+    connections: Mutex<HashMap<u64, Connection>>,
+    threads: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl SubscriberState {
+    /// Forgets the sessions that had no open connection for `expiry`.
+    fn expire_sessions(&self, expiry: Duration) {
+        self.sessions
+            .lock()
+            .unwrap()
+            .retain(|_, session| session.connections > 0 || session.idle_since.elapsed() < expiry);
+    }
+}
+
+struct Session {
+    /// The sequence of the last event republished from the session. The lock is held while
+    /// republishing, so that a connection that replaces another one only resumes once the events
+    /// in flight on the old connection were republished.
+    republished: Arc<Mutex<u64>>,
+    connections: usize,
+    idle_since: Instant,
+}
+
+/// Marks a session as idle once the last of its connections closes.
+struct SessionGuard<'a> {
+    state: &'a SubscriberState,
+    session: u64,
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(session) = self.state.sessions.lock().unwrap().get_mut(&self.session) {
+            session.connections -= 1;
+            session.idle_since = Instant::now();
+        }
+    }
+}
+
+/// Receives events from [`BridgePublisher`](crate::BridgePublisher)s over a TCP or Unix domain
+/// socket, and republishes them onto a local event-bus.
+///
+/// Events are decoded with the bridge's [`TypeRegistry`], by the name their type was registered
+/// under on the publishing side. Each event is acknowledged once it has been republished, so
+/// republishing waits on the local event-bus's subscribers according to its wait-strategy, and
+/// holds back the remote publishers in turn.
+///
+/// The subscriber remembers the last event it republished from each publisher, so events that a
+/// publisher resends after reconnecting are not republished twice. A publisher that stays
+/// disconnected for over a minute is forgotten, after which events it resends may be republished
+/// again.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let registry = TypeRegistry::new().register::<Order>("order");
+/// let eventbus = Eventador::new(1024)?;
+/// let subscriber = eventbus.subscribe::<Order>();
+///
+/// let bridge = BridgeSubscriber::bind(&eventbus, registry, BridgeAddr::tcp("0.0.0.0:9000"))?;
+/// let order = subscriber.recv()?;
+/// ```
+///
+pub struct BridgeSubscriber {
+    state: Arc<SubscriberState>,
+    addr: BridgeAddr,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BridgeSubscriber {
+    /// Listens for bridge publishers at `addr`, and republishes their events onto `eventbus`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if `eventbus` was built with
+    /// [`ProducerType::Single`](crate::ProducerType::Single), since events are republished from the
+    /// bridge's own threads, or [`Error::Bridge`] if the address could not be bound.
+    ///
+    pub fn bind(
+        eventbus: &Eventador,
+        registry: TypeRegistry,
+        addr: BridgeAddr,
+    ) -> Result<Self, Error> {
+        eventbus.ring.require_multi_producer(
+            "bridged events can't be republished onto a single-producer event-bus",
+        )?;
+
+        let listener = addr.bind().map_err(io_error)?;
+        let addr = listener.local_addr().map_err(io_error)?;
+
+        let state = Arc::new(SubscriberState {
+            closed: AtomicBool::new(false),
+            next_connection_id: AtomicU64::new(0),
+            sessions: Mutex::new(HashMap::new()),
+            connections: Mutex::new(HashMap::new()),
+            threads: Mutex::new(Vec::new()),
+        });
+
+        let ring = Arc::downgrade(&eventbus.ring);
+        let accept_state = state.clone();
+
+        let thread = std::thread::Builder::new()
+            .name(String::from("eventador-bridge-listener"))
+            .spawn(move || accept(listener, ring, Arc::new(registry), accept_state))
+            .map_err(io_error)?;
+
+        Ok(Self {
+            state,
+            addr,
+            thread: Some(thread),
+        })
+    }
+
+    /// Get the address the subscriber is listening on, such as the port that was assigned when
+    /// binding to port zero.
+    pub fn local_addr(&self) -> &BridgeAddr {
+        &self.addr
+    }
+
+    /// Closes every open connection, which publishers will retry.
+    #[cfg(test)]
+    fn disconnect(&self) {
+        for connection in self.state.connections.lock().unwrap().values() {
+            connection.shutdown();
+        }
+    }
+}
+
+impl Drop for BridgeSubscriber {
+    fn drop(&mut self) {
+        self.state.closed.store(true, Ordering::Release);
+
+        // Wake up the listener so that it sees the subscriber was closed
+        let _ = self.addr.connect();
+
+        for (_, connection) in self.state.connections.lock().unwrap().drain() {
+            connection.shutdown();
+        }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+
+        // The listener has stopped, so no more connection threads are started
+        for thread in self.state.threads.lock().unwrap().drain(..) {
+            let _ = thread.join();
+        }
+
+        #[cfg(unix)]
+        if let BridgeAddr::Unix(path) = &self.addr {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn accept(
+    listener: Listener,
+    ring: Weak<RingBuffer>,
+    registry: Arc<TypeRegistry>,
+    state: Arc<SubscriberState>,
+) {
+    loop {
+        let connection = listener.accept();
+        if state.closed.load(Ordering::Acquire) {
+            return;
+        }
+
+        let (connection, clone) =
+            match connection.and_then(|c| c.try_clone().map(|clone| (c, clone))) {
+                Ok(connection) => connection,
+                Err(_) => continue,
+            };
+
+        let id = state.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        state.connections.lock().unwrap().insert(id, clone);
+        state.expire_sessions(SESSION_EXPIRY);
+
+        let ring = ring.clone();
+        let registry = registry.clone();
+        let connection_state = state.clone();
+
+        let thread = std::thread::Builder::new()
+            .name(String::from("eventador-bridge-connection"))
+            .spawn(move || {
+                let state = connection_state;
+                let _ = republish(connection, &ring, &registry, &state);
+
+                let connection = state.connections.lock().unwrap().remove(&id);
+                if let Some(connection) = connection {
+                    connection.shutdown();
+                }
+            });
+
+        let mut threads = state.threads.lock().unwrap();
+        threads.retain(|thread| !thread.is_finished());
+        threads.extend(thread.ok());
+    }
+}
+
+/// Republishes the events received over a connection, until the connection or the event-bus is
+/// closed.
+fn republish(
+    connection: Connection,
+    ring: &Weak<RingBuffer>,
+    registry: &TypeRegistry,
+    state: &SubscriberState,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(connection.try_clone()?);
+    let mut writer = BufWriter::new(connection);
+
+    let session = match read_frame(&mut reader)? {
+        (HELLO, body) => decode_sequence(&body)?,
+        _ => return Err(invalid_frame()),
+    };
+
+    let republished = {
+        let mut sessions = state.sessions.lock().unwrap();
+        let session = sessions.entry(session).or_insert_with(|| Session {
+            republished: Arc::default(),
+            connections: 0,
+            idle_since: Instant::now(),
+        });
+
+        session.connections += 1;
+        session.republished.clone()
+    };
+    let _guard = SessionGuard { state, session };
+
+    let resumed = *republished.lock().unwrap();
+    write_frame(&mut writer, RESUME, &[&resumed.to_le_bytes()])?;
+    writer.flush()?;
+
+    let mut unacked = 0;
+    loop {
+        let body = match read_frame(&mut reader)? {
+            (EVENT, body) => body,
+            _ => return Err(invalid_frame()),
+        };
+        let (sequence, name, payload) = decode_event(&body)?;
+
+        let mut last = republished.lock().unwrap();
+
+        // Events that were republished before a reconnection are skipped
+        if sequence > *last {
+            // Publishing is retried so that the subscriber can be closed while the event-bus is
+            // full, but never for less than its own wait, so that lossy claims are not given up on
+            loop {
+                let ring = match ring.upgrade() {
+                    Some(ring) if !state.closed.load(Ordering::Acquire) => ring,
+                    _ => return Ok(()),
+                };
+
+                let timeout = match ring.sequencer().wait_strategy() {
+                    WaitStrategy::WaitForDuration(wait) => std::cmp::max(wait, POLL_INTERVAL),
+                    _ => POLL_INTERVAL,
+                };
+
+                // Events that can't be decoded are acknowledged anyway, since a resend wouldn't
+                // help
+                match registry.publish_timeout(&ring, name, payload, Metadata::default(), timeout) {
+                    Err(Error::Timeout) => continue,
+                    _ => break,
+                }
+            }
+
+            *last = sequence;
+        }
+
+        let acked = *last;
+        drop(last);
+
+        unacked += 1;
+        if reader.buffer().is_empty() || unacked >= ACK_INTERVAL {
+            write_frame(&mut writer, ACK, &[&acked.to_le_bytes()])?;
+            writer.flush()?;
+            unacked = 0;
+        }
+    }
+}
+
+fn decode_event(body: &[u8]) -> std::io::Result<(u64, &str, &[u8])> {
+    let sequence = decode_sequence(body)?;
+
+    let name_len = match body.get(8..10) {
+        Some(len) => u16::from_le_bytes([len[0], len[1]]) as usize,
+        None => return Err(invalid_frame()),
+    };
+
+    match body.get(10..10 + name_len).map(std::str::from_utf8) {
+        Some(Ok(name)) => Ok((sequence, name, &body[10 + name_len..])),
+        _ => Err(invalid_frame()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BridgeAddr, BridgePublisher, BridgeSubscriber, Eventador, TypeRegistry};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn wait_for(f: impl Fn() -> bool) {
+        while !f() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn registry() -> TypeRegistry {
+        TypeRegistry::new().register::<(String, u64)>("order")
+    }
+
+    #[test]
+    fn bridges_events_over_tcp() {
+        let remote = Eventador::new(4).unwrap();
+        let subscriber = remote.subscribe::<(String, u64)>();
+        let bridge_subscriber =
+            BridgeSubscriber::bind(&remote, registry(), BridgeAddr::tcp("127.0.0.1:0")).unwrap();
+
+        let local = Eventador::new(4).unwrap();
+        let _bridge_publisher =
+            BridgePublisher::connect(&local, registry(), bridge_subscriber.local_addr().clone())
+                .unwrap();
+
+        let publish_bus = local.clone();
+        let publish_thread = std::thread::spawn(move || {
+            for i in 1..=20 {
+                publish_bus.publish((String::from("order"), i as u64));
+
+                // Unregistered types are not bridged, and don't hold back local publishers
+                publish_bus.publish(i as usize);
+            }
+        });
+
+        // The remote ring is smaller than the number of events, so the bridge has to wait on
+        // the remote subscriber
+        for i in 1..=20 {
+            assert_eq!(i, subscriber.recv().unwrap().1);
+        }
+
+        publish_thread.join().unwrap();
+        assert_eq!(0, remote.stats().published_count::<usize>());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn resumes_after_reconnecting() {
+        let path =
+            std::env::temp_dir().join(format!("eventador-bridge-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let remote = Eventador::new(16).unwrap();
+        let subscriber = remote.subscribe::<(String, u64)>();
+        let bridge_subscriber =
+            BridgeSubscriber::bind(&remote, registry(), BridgeAddr::unix(&path)).unwrap();
+
+        let local = Eventador::new(16).unwrap();
+        let bridge_publisher =
+            BridgePublisher::connect(&local, registry(), BridgeAddr::unix(&path)).unwrap();
+
+        for i in 1..=5 {
+            local.publish((String::from("order"), i as u64));
+        }
+        for i in 1..=5 {
+            assert_eq!(i, subscriber.recv().unwrap().1);
+        }
+
+        bridge_subscriber.disconnect();
+        for i in 6..=10 {
+            local.publish((String::from("order"), i as u64));
+        }
+
+        // Every event arrives once and in order, across the reconnection
+        for i in 6..=10 {
+            assert_eq!(i, subscriber.recv().unwrap().1);
+        }
+
+        while bridge_publisher.acknowledged() < 10 {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(10, remote.stats().published_count::<(String, u64)>());
+    }
+
+    #[test]
+    fn skips_events_that_do_not_fit_a_frame() {
+        let long_name = "n".repeat(u16::MAX as usize + 1);
+        let registry = || registry().register::<u64>(long_name.as_str());
+
+        let remote = Eventador::new(4).unwrap();
+        let subscriber = remote.subscribe::<(String, u64)>();
+        let bridge_subscriber =
+            BridgeSubscriber::bind(&remote, registry(), BridgeAddr::tcp("127.0.0.1:0")).unwrap();
+
+        let local = Eventador::new(4).unwrap();
+        let _bridge_publisher =
+            BridgePublisher::connect(&local, registry(), bridge_subscriber.local_addr().clone())
+                .unwrap();
+
+        // The type name can't be framed, so the event is skipped rather than resent forever
+        local.publish(7_u64);
+        local.publish((String::from("order"), 1_u64));
+
+        assert_eq!(1, subscriber.recv().unwrap().1);
+        assert_eq!(0, remote.stats().published_count::<u64>());
+    }
+
+    #[test]
+    fn expires_idle_sessions() {
+        let remote = Eventador::new(4).unwrap();
+        let subscriber = remote.subscribe::<(String, u64)>();
+        let bridge_subscriber =
+            BridgeSubscriber::bind(&remote, registry(), BridgeAddr::tcp("127.0.0.1:0")).unwrap();
+
+        let local = Eventador::new(4).unwrap();
+        let bridge_publisher =
+            BridgePublisher::connect(&local, registry(), bridge_subscriber.local_addr().clone())
+                .unwrap();
+
+        local.publish((String::from("order"), 1_u64));
+        assert_eq!(1, subscriber.recv().unwrap().1);
+
+        // The session of a connected publisher is kept, however long it was open
+        let state = &bridge_subscriber.state;
+        state.expire_sessions(Duration::ZERO);
+        assert_eq!(1, state.sessions.lock().unwrap().len());
+
+        drop(bridge_publisher);
+        wait_for(|| state.connections.lock().unwrap().is_empty());
+
+        state.expire_sessions(Duration::ZERO);
+        assert!(state.sessions.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn stops_republishing_once_dropped() {
+        let remote = Eventador::new(4).unwrap();
+        let _stalled = remote.subscribe::<(String, u64)>();
+        let bridge_subscriber =
+            BridgeSubscriber::bind(&remote, registry(), BridgeAddr::tcp("127.0.0.1:0")).unwrap();
+
+        let local = Eventador::new(16).unwrap();
+        let _bridge_publisher =
+            BridgePublisher::connect(&local, registry(), bridge_subscriber.local_addr().clone())
+                .unwrap();
+
+        for i in 1..=10 {
+            local.publish((String::from("order"), i as u64));
+        }
+
+        // The remote subscriber never reads, so republishing waits on it
+        wait_for(|| remote.stats().cursor == 4);
+        drop(bridge_subscriber);
+
+        // The connection thread was joined, and no longer holds on to the remote event-bus
+        assert_eq!(2, Arc::strong_count(&remote.ring));
+    }
+}
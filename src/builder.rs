@@ -0,0 +1,356 @@
+use crate::error::Error;
+use crate::eviction::EvictionPolicy;
+use crate::hooks::Hooks;
+#[cfg(feature = "journal")]
+use crate::journal::Journal;
+use crate::ring_buffer::RingBuffer;
+use crate::wait_strategy::{Backoff, WaitStrategy};
+use crate::Eventador;
+use std::sync::Arc;
+
+/// Declares how many threads may publish to the event-bus concurrently.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ProducerType {
+    /// *Default*: Any number of publishers may publish concurrently.
+    Multi,
+
+    /// Only one thread will ever publish at a time, which lets sequences be claimed without a CAS
+    /// loop.
+    ///
+    /// **Publishing from multiple threads concurrently will lose events.**
+    ///
+    /// Features that publish from threads of their own are rejected with
+    /// [`Error::InvalidConfig`]: scheduled events, forwarding to the event-bus, dead letters and
+    /// bridge subscribers. Requests, responses and journal recovery publish from the calling
+    /// thread, so they must be called from the thread that publishes.
+    Single,
+}
+
+/// Declares where new subscribers start reading from in the ring.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StartPosition {
+    /// *Default*: Only receive events that are published after subscribing.
+    Next,
+
+    /// Start from the most recently published event.
+    Latest,
+
+    /// Start from the oldest event that is still held in the ring.
+    Earliest,
+}
+
+/// A builder to configure and create an [`Eventador`] event-bus.
+///
+/// All options are validated when the event-bus is built.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::builder()
+///     .name("orders")
+///     .capacity(1024)
+///     .producer_type(ProducerType::Single)
+///     .wait_strategy(WaitStrategy::AllSubscribers)
+///     .backoff(Backoff::Yield)
+///     .start_position(StartPosition::Latest)
+///     .build()?;
+/// ```
+///
+#[derive(Clone)]
+pub struct EventadorBuilder {
+    pub(crate) name: Option<String>,
+    pub(crate) capacity: u64,
+    pub(crate) producer_type: ProducerType,
+    pub(crate) wait_strategy: WaitStrategy,
+    pub(crate) backoff: Backoff,
+    pub(crate) eviction_policy: Option<EvictionPolicy>,
+    pub(crate) start_position: StartPosition,
+    pub(crate) hooks: Option<Arc<dyn Hooks>>,
+    pub(crate) track_latency: bool,
+    #[cfg(feature = "journal")]
+    pub(crate) journal: Option<Journal>,
+}
+
+impl EventadorBuilder {
+    /// Creates a builder with a capacity of 256 and the default options.
+    pub fn new() -> Self {
+        Self {
+            name: None,
+            capacity: 256,
+            producer_type: ProducerType::Multi,
+            wait_strategy: WaitStrategy::AllSubscribers,
+            backoff: Backoff::default(),
+            eviction_policy: None,
+            start_position: StartPosition::Next,
+            hooks: None,
+            track_latency: cfg!(feature = "metrics"),
+            #[cfg(feature = "journal")]
+            journal: None,
+        }
+    }
+
+    /// Names the event-bus, to identify it in metrics and traces.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the number of events the ring can hold.
+    ///
+    /// **The capacity is required to be a power of 2.**
+    pub fn capacity(mut self, capacity: u64) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets whether one or many threads will publish to the event-bus.
+    pub fn producer_type(mut self, producer_type: ProducerType) -> Self {
+        self.producer_type = producer_type;
+        self
+    }
+
+    /// Sets the policy for overwriting events that subscribers have not read yet.
+    pub fn wait_strategy(mut self, wait_strategy: WaitStrategy) -> Self {
+        self.wait_strategy = wait_strategy;
+        self
+    }
+
+    /// Sets how publishers wait while the ring is full of unread events.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the policy for evicting lagging subscribers.
+    pub fn eviction_policy(mut self, eviction_policy: EvictionPolicy) -> Self {
+        self.eviction_policy = Some(eviction_policy);
+        self
+    }
+
+    /// Sets where new subscribers start reading from in the ring.
+    pub fn start_position(mut self, start_position: StartPosition) -> Self {
+        self.start_position = start_position;
+        self
+    }
+
+    /// Registers [`Hooks`] to be called as events move through the event-bus.
+    pub fn hooks(mut self, hooks: impl Hooks + 'static) -> Self {
+        self.hooks = Some(Arc::new(hooks));
+        self
+    }
+
+    /// Sets whether publishers timestamp events, so that subscribers can record how long each event
+    /// spent in the ring before being read in a [`LatencyHistogram`](crate::LatencyHistogram).
+    ///
+    /// This is disabled by default, unless the `metrics` feature is enabled. While disabled, no
+    /// timestamps are taken and no latencies are recorded.
+    pub fn track_latency(mut self, enabled: bool) -> Self {
+        self.track_latency = enabled;
+        self
+    }
+
+    /// Appends every published event of the [`Journal`]'s registered types to its log on disk.
+    #[cfg(feature = "journal")]
+    pub fn journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Validates the options and creates the [`Eventador`] event-bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCapacity`] if the capacity is not a power of two greater than one,
+    /// or [`Error::InvalidConfig`] if any other option is invalid.
+    pub fn build(self) -> Result<Eventador, Error> {
+        let eventbus = Eventador::from(self.build_ring()?);
+
+        #[cfg(feature = "journal")]
+        eventbus.ring.start_journal()?;
+
+        Ok(eventbus)
+    }
+
+    pub(crate) fn build_ring(self) -> Result<RingBuffer, Error> {
+        self.validate()?;
+        Ok(RingBuffer::from_builder(self))
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.capacity < 2 || !self.capacity.is_power_of_two() {
+            return Err(Error::InvalidCapacity(self.capacity));
+        }
+
+        if let Some(name) = &self.name {
+            if name.trim().is_empty() {
+                return Err(Error::InvalidConfig("name must not be empty"));
+            }
+        }
+
+        #[cfg(feature = "journal")]
+        if let Some(journal) = &self.journal {
+            journal.validate()?;
+        }
+
+        if let Some(policy) = &self.eviction_policy {
+            if policy.lag_limit() == Some(0) {
+                return Err(Error::InvalidConfig(
+                    "eviction policy's max lag must be greater than zero",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for EventadorBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::any::TypeId;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn validates_options() {
+        assert_eq!(
+            Some(Error::InvalidCapacity(12)),
+            Eventador::builder().capacity(12).build().err()
+        );
+
+        assert!(matches!(
+            Eventador::builder().name(" ").build(),
+            Err(Error::InvalidConfig(_))
+        ));
+
+        assert!(matches!(
+            Eventador::builder()
+                .eviction_policy(EvictionPolicy::new().max_lag(0))
+                .build(),
+            Err(Error::InvalidConfig(_))
+        ));
+
+        let eventbus = Eventador::builder().name("orders").build().unwrap();
+        assert_eq!(Some("orders"), eventbus.name());
+    }
+
+    #[test]
+    fn rejects_publishing_threads_on_single_producer() {
+        let single = Eventador::builder()
+            .producer_type(ProducerType::Single)
+            .build()
+            .unwrap();
+        let multi = Eventador::new(4).unwrap();
+
+        assert!(matches!(
+            single.publish_after(std::time::Duration::from_millis(1), 1_usize),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            multi.forward::<usize>(&single),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            multi.handler::<usize>().dead_letters(&single).spawn(|_| {}),
+            Err(Error::InvalidConfig(_))
+        ));
+
+        // Forwarding from a single-producer event-bus only reads from it
+        assert!(single.forward::<usize>(&multi).is_ok());
+    }
+
+    #[test]
+    fn single_producer() {
+        let eventbus = Eventador::builder()
+            .capacity(4)
+            .producer_type(ProducerType::Single)
+            .backoff(Backoff::Yield)
+            .build()
+            .unwrap();
+
+        let subscriber = eventbus.subscribe::<usize>();
+
+        let publish_bus = eventbus.clone();
+        let publish_thread = std::thread::spawn(move || {
+            for i in 1..=100_usize {
+                publish_bus.publish(i);
+            }
+        });
+
+        for i in 1..=100_usize {
+            assert_eq!(i, *subscriber.recv().unwrap());
+        }
+
+        publish_thread.join().unwrap();
+    }
+
+    #[test]
+    fn start_positions() {
+        let eventbus = Eventador::builder()
+            .capacity(4)
+            .wait_strategy(WaitStrategy::NoWait)
+            .start_position(StartPosition::Earliest)
+            .build()
+            .unwrap();
+
+        for i in 1..=6_usize {
+            eventbus.publish(i);
+        }
+
+        let earliest = eventbus.subscribe::<usize>();
+        assert_eq!(3, *earliest.recv().unwrap());
+
+        let latest = eventbus.subscribe_from::<usize>(StartPosition::Latest);
+        assert_eq!(6, *latest.recv().unwrap());
+
+        let next = eventbus.subscribe_from::<usize>(StartPosition::Next);
+        eventbus.publish(7_usize);
+        assert_eq!(7, *next.recv().unwrap());
+    }
+
+    #[derive(Default)]
+    struct CountingHooks {
+        published: AtomicU64,
+        received: AtomicU64,
+    }
+
+    impl Hooks for CountingHooks {
+        fn on_publish(&self, _sequence: u64, type_id: TypeId) {
+            assert_eq!(TypeId::of::<usize>(), type_id);
+            self.published.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn on_receive(&self, _subscriber_id: u64, _sequence: u64) {
+            self.received.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn calls_hooks() {
+        let hooks = Arc::new(CountingHooks::default());
+        let eventbus = Eventador::builder()
+            .capacity(4)
+            .hooks(hooks.clone())
+            .build()
+            .unwrap();
+
+        let subscriber = eventbus.subscribe::<usize>();
+
+        eventbus.publish(1_usize);
+        eventbus.publisher().send(2_usize);
+
+        subscriber.recv().unwrap();
+        subscriber.recv().unwrap();
+
+        assert_eq!(2, hooks.published.load(Ordering::Relaxed));
+        assert_eq!(2, hooks.received.load(Ordering::Relaxed));
+    }
+}
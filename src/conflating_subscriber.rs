@@ -0,0 +1,321 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crossbeam::sync::Parker;
+
+use crate::error::Error;
+use crate::event::EventRead;
+use crate::ring_buffer::RingBuffer;
+use crate::sequence::gating_sequence::GatingSequence;
+use crate::waiter::Alerter;
+
+/// A handle to receive only the newest unread event per key from the event-bus.
+///
+/// Every call to [`ConflatingSubscriber::recv`] first drains all events that have been published
+/// so far, collapsing unread events that share a key so that only the newest one is handed out.
+/// The newest events are held by the subscriber itself rather than in the ring, so its gating
+/// sequence moves past every event it has drained. Publishers are only held back once a ring's
+/// worth of events is published between two reads, however many keys are waiting to be received.
+///
+/// Events for different keys are received in the order they were published.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(8)?;
+/// let mut subscriber = eventbus.subscribe_conflated::<(char, usize), char>(|tick| tick.0);
+///
+/// eventbus.publish(('a', 1));
+/// eventbus.publish(('b', 1));
+/// eventbus.publish(('a', 2));
+///
+/// assert_eq!(('b', 1), *subscriber.recv()?);
+/// assert_eq!(('a', 2), *subscriber.recv()?);
+/// ```
+///
+pub struct ConflatingSubscriber<T, K> {
+    ring: Arc<RingBuffer>,
+    sequence: Arc<GatingSequence>,
+    cursor: u64,
+    pending: HashMap<K, Pending<T>>,
+
+    /// The keys of the pending events by the sequence they were published at, oldest first.
+    order: BTreeMap<u64, K>,
+    key_fn: Box<dyn Fn(&T) -> K + Send>,
+    parker: Parker,
+}
+
+impl<T: 'static, K> ConflatingSubscriber<T, K>
+where
+    T: Send,
+    K: Eq + Hash + Clone,
+{
+    pub(crate) fn new(
+        ring: Arc<RingBuffer>,
+        sequence: Arc<GatingSequence>,
+        key_fn: Box<dyn Fn(&T) -> K + Send>,
+    ) -> Self {
+        let cursor = sequence.get();
+
+        Self {
+            ring,
+            sequence,
+            cursor,
+            pending: HashMap::new(),
+            order: BTreeMap::new(),
+            key_fn,
+            parker: Parker::new(),
+        }
+    }
+
+    /// Get the current internal sequence number for the [`ConflatingSubscriber`].
+    ///
+    /// This is the sequence of the next event to be drained from the event-bus. Events before it
+    /// that are still waiting to be received are held by the subscriber.
+    pub fn sequence(&self) -> u64 {
+        self.sequence.get()
+    }
+
+    /// Get the id that identifies the [`ConflatingSubscriber`] on the event-bus, such as in an
+    /// [`Eviction`](crate::Eviction).
+    pub fn id(&self) -> u64 {
+        self.sequence.id()
+    }
+
+    /// Get the number of keys that have an unread event.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Synchronously read the oldest of the newest events per key from the event-bus.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut subscriber = eventbus.subscribe_conflated::<(char, usize), char>(|tick| tick.0);
+    ///
+    /// eventbus.publish(('a', 1));
+    /// eventbus.publish(('a', 2));
+    ///
+    /// assert_eq!(('a', 2), *subscriber.recv()?);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the ConflatingSubscriber was evicted from the event-bus for
+    /// lagging.
+    ///
+    pub fn recv(&mut self) -> Result<EventRead<T>, Error> {
+        loop {
+            if self.sequence.is_evicted() {
+                return Err(Error::Evicted);
+            }
+
+            self.drain_published();
+
+            if let Some(event) = self.take_oldest() {
+                return Ok(event);
+            }
+
+            self.wait_for_publish();
+        }
+    }
+
+    fn drain_published(&mut self) {
+        loop {
+            let envelope = self
+                .ring
+                .get_envelope(self.cursor)
+                .expect("ring buffer was not pre-populated with empty event envelopes");
+
+            let envelope_sequence = envelope.sequence();
+            if self.cursor == envelope_sequence {
+                if let Some(event) = envelope.read::<T>() {
+                    let key = (self.key_fn)(&event);
+                    let pending = Pending {
+                        sequence: self.cursor,
+                        published_at: envelope.published_at(),
+                        event,
+                    };
+
+                    // The superseded event, if any, is dropped along with its reference count
+                    self.order.insert(self.cursor, key.clone());
+                    if let Some(superseded) = self.pending.insert(key, pending) {
+                        self.order.remove(&superseded.sequence);
+                    }
+                }
+
+                self.cursor += 1;
+            } else if self.cursor < envelope_sequence {
+                // Publisher has overwritten an event that has not been read yet
+                let overwritten =
+                    self.ring
+                        .record_overwritten(self.id(), self.cursor, envelope_sequence);
+                self.sequence.add_lagged(overwritten);
+
+                self.cursor = envelope_sequence;
+            } else {
+                break;
+            }
+        }
+
+        self.update_gating_sequence();
+    }
+
+    fn take_oldest(&mut self) -> Option<EventRead<T>> {
+        let (_, key) = self.order.pop_first()?;
+
+        let pending = self.pending.remove(&key)?;
+        self.ring
+            .record_receive_at(&self.sequence, pending.sequence, pending.published_at);
+
+        Some(pending.event)
+    }
+
+    fn wait_for_publish(&self) {
+        let envelope = self
+            .ring
+            .get_envelope(self.cursor)
+            .expect("ring buffer was not pre-populated with empty event envelopes");
+
+        let alerter = Alerter::unparker(&self.parker);
+        self.sequence
+            .waiter()
+            .wait(&envelope, self.cursor, alerter, || self.parker.park());
+    }
+
+    fn update_gating_sequence(&self) {
+        self.sequence.set(self.cursor);
+    }
+}
+
+/// The newest unread event for a key, which is held outside the ring until it is received.
+struct Pending<T> {
+    sequence: u64,
+    published_at: u64,
+    event: EventRead<T>,
+}
+
+impl<T, K> Drop for ConflatingSubscriber<T, K> {
+    fn drop(&mut self) {
+        self.ring
+            .sequencer()
+            .deregister_gating_sequence(&self.sequence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Eventador, WaitStrategy};
+
+    #[derive(Debug, PartialEq)]
+    struct Tick {
+        symbol: &'static str,
+        price: usize,
+    }
+
+    #[test]
+    fn receives_newest_per_key() {
+        let eventbus = Eventador::new(8).unwrap();
+        let mut subscriber = eventbus.subscribe_conflated::<Tick, &'static str>(|t| t.symbol);
+
+        for price in 1..=3 {
+            eventbus.publish(Tick { symbol: "A", price });
+            eventbus.publish(Tick {
+                symbol: "B",
+                price: price * 10,
+            });
+        }
+        eventbus.publish(Tick {
+            symbol: "A",
+            price: 4,
+        });
+
+        assert_eq!(
+            Tick {
+                symbol: "B",
+                price: 30
+            },
+            *subscriber.recv().unwrap()
+        );
+        assert_eq!(1, subscriber.pending());
+        assert_eq!(8, subscriber.sequence());
+
+        assert_eq!(
+            Tick {
+                symbol: "A",
+                price: 4
+            },
+            *subscriber.recv().unwrap()
+        );
+        assert_eq!(0, subscriber.pending());
+        assert_eq!(8, subscriber.sequence());
+    }
+
+    #[test]
+    fn slow_subscriber_does_not_throttle_publishers() {
+        let eventbus = Eventador::new(4).unwrap();
+        let mut subscriber = eventbus.subscribe_conflated::<Tick, &'static str>(|t| t.symbol);
+
+        let publish_bus = eventbus.clone();
+        let publish_thread = std::thread::spawn(move || {
+            for price in 1..=100 {
+                publish_bus.publish(Tick { symbol: "A", price });
+            }
+        });
+
+        let mut last = 0;
+        while last < 100 {
+            let tick = subscriber.recv().unwrap();
+            assert!(tick.price > last);
+            last = tick.price;
+        }
+
+        publish_thread.join().unwrap();
+    }
+
+    #[test]
+    fn pending_events_do_not_gate_publishers() {
+        let eventbus = Eventador::new(4).unwrap();
+        let mut subscriber = eventbus.subscribe_conflated::<Tick, &'static str>(|t| t.symbol);
+        let mut publisher = eventbus.publisher();
+
+        for symbol in ["A", "B", "C"] {
+            eventbus.publish(Tick { symbol, price: 1 });
+        }
+
+        assert_eq!("A", subscriber.recv().unwrap().symbol);
+        assert_eq!(2, subscriber.pending());
+
+        // The events for "B" and "C" are held by the subscriber, so the whole ring is free again
+        for price in 2..=5 {
+            assert!(publisher.try_send(Tick { symbol: "D", price }).is_ok());
+        }
+
+        assert_eq!("B", subscriber.recv().unwrap().symbol);
+        assert_eq!("C", subscriber.recv().unwrap().symbol);
+        assert_eq!(5, subscriber.recv().unwrap().price);
+    }
+
+    #[test]
+    fn counts_overwritten_events_as_lag() {
+        let eventbus = Eventador::with_strategy(4, WaitStrategy::NoWait).unwrap();
+        let mut subscriber = eventbus.subscribe_conflated::<Tick, usize>(|t| t.price);
+
+        for price in 1..=10 {
+            eventbus.publish(Tick { symbol: "A", price });
+        }
+
+        // Like any other subscriber, the events overwritten before they were drained are lag, while
+        // the ones it skipped that were still held in the ring are not
+        assert_eq!(9, subscriber.recv().unwrap().price);
+        assert_eq!(6, subscriber.sequence.lagged());
+        assert_eq!(1, subscriber.pending());
+    }
+}
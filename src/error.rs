@@ -0,0 +1,131 @@
+use crate::publisher::TrySendError;
+
+/// An error returned by the sync and async APIs of the event-bus.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// match Eventador::new(3) {
+///     Err(Error::InvalidCapacity(capacity)) => println!("{} is not a power of two", capacity),
+///     _ => unreachable!(),
+/// }
+/// ```
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The capacity of the ring is not a power of two that is greater than one.
+    InvalidCapacity(u64),
+
+    /// An option passed to the [`EventadorBuilder`](crate::EventadorBuilder) is invalid.
+    InvalidConfig(&'static str),
+
+    /// The number of sequences to claim at once is zero, or greater than the capacity of the ring.
+    InvalidBatchSize(u64),
+
+    /// The sequence has not been published yet.
+    InvalidSequence(u64),
+
+    /// The event-bus was closed.
+    Closed,
+
+    /// The ring is full of events that subscribers have not read yet.
+    Full,
+
+    /// The ring stayed full for the entire duration of a timeout.
+    Timeout,
+
+    /// The sequence has already been overwritten in the ring.
+    Lagged(u64),
+
+    /// The subscriber was evicted from the event-bus for lagging.
+    Evicted,
+
+    /// The journal could not write, read or decode events.
+    Journal(String),
+
+    /// The type of an event, or the name it was encoded under, is not registered in the
+    /// [`TypeRegistry`](crate::TypeRegistry).
+    UnregisteredType(String),
+
+    /// An event could not be encoded or decoded.
+    Codec(String),
+
+    /// A shared ring could not be created, attached to, or mapped into memory.
+    SharedMemory(String),
+
+    /// A bridge could not listen for or connect to another event-bus.
+    Bridge(String),
+
+    /// A thread of the event-bus, such as that of a forwarder, could not be started.
+    Spawn(String),
+
+    /// No reply to a request arrived within the timeout.
+    ReplyTimeout,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::InvalidCapacity(capacity) => write!(
+                f,
+                "expected capacity as a power of two greater than one, got {}",
+                capacity
+            ),
+
+            Error::InvalidConfig(reason) => {
+                write!(f, "invalid event-bus configuration: {}", reason)
+            }
+
+            Error::InvalidBatchSize(n) => write!(
+                f,
+                "expected batch size greater than zero and within the ring capacity, got {}",
+                n
+            ),
+
+            Error::InvalidSequence(sequence) => {
+                write!(f, "sequence {} has not been published yet", sequence)
+            }
+
+            Error::Closed => write!(f, "event-bus was closed"),
+
+            Error::Full => write!(f, "ring is full of unread events"),
+
+            Error::Timeout => write!(f, "timed out waiting for an overwriteable envelope"),
+
+            Error::Lagged(sequence) => write!(
+                f,
+                "sequence {} has already been overwritten in the ring",
+                sequence
+            ),
+
+            Error::Evicted => write!(f, "subscriber was evicted from the event-bus for lagging"),
+
+            Error::Journal(reason) => write!(f, "journal failed: {}", reason),
+
+            Error::UnregisteredType(name) => write!(f, "type {} is not registered", name),
+
+            Error::Codec(reason) => write!(f, "failed to encode or decode event: {}", reason),
+
+            Error::SharedMemory(reason) => write!(f, "shared memory failed: {}", reason),
+
+            Error::Bridge(reason) => write!(f, "bridge failed: {}", reason),
+
+            Error::Spawn(reason) => write!(f, "failed to start thread: {}", reason),
+
+            Error::ReplyTimeout => write!(f, "timed out waiting for a reply"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl<T> From<TrySendError<T>> for Error {
+    fn from(err: TrySendError<T>) -> Self {
+        match err {
+            TrySendError::Full(_) => Error::Full,
+            TrySendError::Timeout(_) => Error::Timeout,
+        }
+    }
+}
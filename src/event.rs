@@ -1,19 +1,110 @@
-use crate::alertable::Alertable;
-use crossbeam::epoch::{pin, Atomic, Guard, Owned};
-use lockfree::queue::Queue;
+use crossbeam::epoch::{pin, unprotected, Atomic, Owned};
 use std::any::{Any, TypeId};
 use std::ops::Deref;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Details about how an event was published, carried alongside it in the ring.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(not(feature = "journal"), allow(dead_code))]
+pub(crate) struct Metadata {
+    /// The time the event was published at as given by [`latency::now`](crate::latency::now), or
+    /// zero if it was published without a timestamp.
+    pub published_at: u64,
+
+    /// Whether the event was replayed from a journal rather than newly published.
+    pub replayed: bool,
+
+    /// The id of the event-bus the event was first published on, which is kept as the event is
+    /// forwarded between event-buses. Zero until the event is written into a ring.
+    pub origin: u64,
+
+    /// The ids of the event-buses the event was forwarded through before reaching this one,
+    /// starting with its origin, or `None` if it wasn't forwarded.
+    pub route: Option<Arc<[u64]>>,
+
+    /// The correlation id of the request, if the event was published as one.
+    pub request_id: u64,
+
+    /// The correlation id of the request that the event replies to, if it was published as a
+    /// reply.
+    pub reply_to: u64,
+}
 
 #[derive(Debug)]
 pub(crate) struct Event {
     pub type_id: TypeId,
-    pub data: Box<dyn Any + Send + Sync>,
+    pub data: Arc<dyn Any + Send + Sync>,
+    pub metadata: Metadata,
+    #[cfg(feature = "tracing")]
+    pub span: tracing::Span,
+}
+
+/// An event of any type, for publishing events of more than one type through the same handle,
+/// such as an untyped [`AsyncPublisher`](crate::AsyncPublisher).
+///
+/// The event is published as the type it was created from, so subscribers receive it as that type
+/// rather than as an [`AnyEvent`].
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let mut publisher: AsyncPublisher = eventbus.async_publisher(16);
+///
+/// publisher.send(AnyEvent::new(1234_usize)).await?;
+/// publisher.send(AnyEvent::new(String::from("eventador"))).await?;
+/// ```
+///
+pub struct AnyEvent {
+    type_id: TypeId,
+    type_name: &'static str,
+    data: Arc<dyn Any + Send + Sync>,
+}
+
+impl AnyEvent {
+    /// Wraps an event of type `T`. An event that already is an [`AnyEvent`] is returned as is.
+    pub fn new<T: 'static + Send + Sync>(event: T) -> Self {
+        let mut event = Some(event);
+
+        if let Some(any) = (&mut event as &mut dyn Any).downcast_mut::<Option<AnyEvent>>() {
+            return any.take().expect("event was taken");
+        }
+
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            data: Arc::new(event.expect("event was taken")),
+        }
+    }
+
+    /// Returns whether the event is of type `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    /// Get the name of the event's type, as given by [`std::any::type_name`].
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub(crate) fn event_type_id(&self) -> TypeId {
+        self.type_id
+    }
+}
+
+impl std::fmt::Debug for AnyEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("AnyEvent")
+            .field("type_name", &self.type_name)
+            .finish_non_exhaustive()
+    }
 }
 
 /// A wrapper that can be de-referenced to access and read the event.
 ///
-/// Implements the [`Deref`] and ['AsRef`] traits to access the wrapped event.
+/// Implements the [`Deref`] and [`AsRef`] traits to access the wrapped event.
 ///
 /// # Example
 ///
@@ -29,13 +120,98 @@ pub(crate) struct Event {
 /// let read_msg: &String = readable_event.as_ref();
 /// ```
 ///
-pub struct EventRead<'a, T: 'a> {
-    _guard: Guard,
+/// An [`EventRead`] holds a reference count on the event, so the event stays readable for as long
+/// as it is held, even once it is overwritten in the ring, without holding back the reclamation
+/// of any other event. Use [`EventRead::into_shared`] for a handle that can be cloned.
+///
+pub struct EventRead<T> {
+    data: Arc<dyn Any + Send + Sync>,
+
+    /// Points into `data`, which was checked to hold a `T` when it was read.
     raw: *const T,
-    _marker: std::marker::PhantomData<&'a T>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+#[cfg(feature = "tracing")]
+impl<T> EventRead<T> {
+    /// Get the span for receiving the event, which follows from the span that was current when
+    /// the event was published.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let event = subscriber.recv()?;
+    /// let _entered = event.span().enter();
+    ///
+    /// tracing::info!("handling event");
+    /// ```
+    ///
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+}
+
+impl<T> EventRead<T> {
+    /// Reads the same event again, such as for an event that belongs to overlapping windows.
+    pub(crate) fn duplicate(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            raw: self.raw,
+            #[cfg(feature = "tracing")]
+            span: self.span.clone(),
+        }
+    }
+}
+
+impl<T: 'static + Send + Sync> EventRead<T> {
+    /// Converts the event into a [`SharedEvent`], which shares ownership of it with the ring and
+    /// can be sent to, and held on, any thread.
+    ///
+    /// The event is not copied, and stays alive as long as any [`SharedEvent`] of it does, even
+    /// after it is overwritten in the ring.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let event = subscriber.recv()?.into_shared();
+    ///
+    /// std::thread::spawn(move || process(&event));
+    /// ```
+    ///
+    pub fn into_shared(self) -> SharedEvent<T> {
+        SharedEvent {
+            inner: self
+                .data
+                .downcast::<T>()
+                .unwrap_or_else(|_| unreachable!("event was read as a different type")),
+            #[cfg(feature = "tracing")]
+            span: self.span,
+        }
+    }
+}
+
+impl<T: Clone> EventRead<T> {
+    /// Clones the event out of the ring.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let msg: String = subscriber.recv()?.cloned();
+    /// ```
+    ///
+    pub fn cloned(&self) -> T {
+        (**self).clone()
+    }
 }
 
-impl<'a, T> Deref for EventRead<'a, T> {
+impl<T> Deref for EventRead<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -43,120 +219,208 @@ impl<'a, T> Deref for EventRead<'a, T> {
     }
 }
 
-impl<'a, T> AsRef<T> for EventRead<'a, T> {
+impl<T> AsRef<T> for EventRead<T> {
     fn as_ref(&self) -> &T {
-        &*self
+        self
     }
 }
 
-pub(crate) struct EventEnvelope {
-    sequence: AtomicU64,
-    event: Atomic<Event>,
-    num_waiting: AtomicU64,
-    subscribers: Queue<Option<Box<dyn Alertable + Send + Sync>>>,
+// The event is owned through `data`, which is only ever shared between threads as a `T`
+unsafe impl<T: Send + Sync> Send for EventRead<T> {}
+unsafe impl<T: Send + Sync> Sync for EventRead<T> {}
+
+/// An event that was read from the event-bus, whose ownership is shared with the ring, created by
+/// [`EventRead::into_shared`].
+///
+/// Unlike an [`EventRead`], a [`SharedEvent`] can be cloned, which shares the same event rather
+/// than copying it.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let event = subscriber.recv()?.into_shared();
+///
+/// for worker in &workers {
+///     worker.send(event.clone())?;
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct SharedEvent<T> {
+    inner: Arc<T>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
-impl EventEnvelope {
-    pub fn new() -> Self {
+impl<T> SharedEvent<T> {
+    /// Get the [`Arc`] that the event is shared through.
+    pub fn into_arc(self) -> Arc<T> {
+        self.inner
+    }
+
+    /// Get the span for receiving the event, which follows from the span that was current when
+    /// the event was published.
+    #[cfg(feature = "tracing")]
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+}
+
+impl<T> Clone for SharedEvent<T> {
+    fn clone(&self) -> Self {
         Self {
-            sequence: AtomicU64::new(0),
-            event: Atomic::null(),
-            num_waiting: AtomicU64::new(0),
-            subscribers: Queue::new(),
+            inner: self.inner.clone(),
+            #[cfg(feature = "tracing")]
+            span: self.span.clone(),
         }
     }
+}
 
-    pub fn sequence(&self) -> u64 {
-        self.sequence.load(Ordering::Acquire)
-    }
+impl<T> Deref for SharedEvent<T> {
+    type Target = T;
 
-    pub fn start_waiting(&self) {
-        self.num_waiting.fetch_add(1, Ordering::Acquire);
+    fn deref(&self) -> &Self::Target {
+        &self.inner
     }
+}
 
-    pub fn stop_waiting(&self) {
-        self.num_waiting.fetch_sub(1, Ordering::Release);
+impl<T> AsRef<T> for SharedEvent<T> {
+    fn as_ref(&self) -> &T {
+        &self.inner
     }
+}
 
-    pub fn add_subscriber(&self, alerter: Box<dyn Alertable + Send + Sync>) {
-        self.subscribers.push(Some(alerter));
+/// The event held in an [`EventEnvelope`].
+///
+/// Readers take their own reference count on the event's data, and only pin the epoch while they
+/// do, so a replaced event is reclaimed as soon as the epoch moves on, however long readers hold on
+/// to it.
+pub(crate) struct EventSlot {
+    event: Atomic<Event>,
+}
+
+impl EventSlot {
+    pub fn new() -> Self {
+        Self {
+            event: Atomic::null(),
+        }
     }
 
-    pub unsafe fn read<'a, T: 'static>(&self) -> Option<EventRead<'a, T>> {
+    /// Reads the event in the slot if it is of type `T`.
+    pub fn read<T: 'static>(&self, _sequence: u64) -> Option<EventRead<T>> {
         let guard = pin();
 
-        let event = self.event.load(Ordering::Acquire, &guard).as_raw();
+        // The event can't be reclaimed while the epoch is pinned
+        let event = unsafe { self.event.load(Ordering::Acquire, &guard).as_ref() }?;
 
-        if !event.is_null() && TypeId::of::<T>() == (*event).type_id {
-            if let Some(event_data) = (*event).data.downcast_ref() {
-                return Some(EventRead {
-                    _guard: guard,
-                    raw: &*event_data,
-                    _marker: std::marker::PhantomData,
-                });
-            }
+        if TypeId::of::<T>() != event.type_id {
+            return None;
         }
 
-        return None;
+        let data = event.data.clone();
+        let raw: *const T = data.downcast_ref::<T>()?;
+
+        #[cfg(feature = "tracing")]
+        let span = {
+            let span = tracing::trace_span!("eventador.receive", sequence = _sequence);
+            span.follows_from(&event.span);
+            span
+        };
+
+        Some(EventRead {
+            data,
+            raw,
+            #[cfg(feature = "tracing")]
+            span,
+        })
     }
 
-    pub(crate) fn overwrite<T: 'static + Send + Sync>(&self, sequence: u64, data: T) {
-        let mut event = Owned::new(Event {
-            type_id: TypeId::of::<T>(),
-            data: Box::new(data),
-        });
+    /// Calls `f` with the event in the slot, whatever its type.
+    pub fn with_event<R>(&self, f: impl FnOnce(&Event) -> R) -> Option<R> {
+        let guard = pin();
+        unsafe { self.event.load(Ordering::Acquire, &guard).as_ref() }.map(f)
+    }
 
+    /// Replaces the event in the slot, and defers dropping the old event until no reader can be
+    /// taking a reference count on it anymore.
+    pub fn replace(&self, event: Event) {
         let guard = pin();
+        let old = self.event.swap(Owned::new(event), Ordering::AcqRel, &guard);
+
+        if !old.is_null() {
+            unsafe {
+                guard.defer_destroy(old);
+            }
+        }
+    }
+}
+
+impl Drop for EventSlot {
+    fn drop(&mut self) {
+        // Readers hold on to events through their own reference counts rather than the slot, so
+        // the last event can be dropped along with it
+        unsafe {
+            let event = self.event.load(Ordering::Acquire, unprotected());
+
+            if !event.is_null() {
+                drop(event.into_owned());
+            }
+        }
+    }
+}
 
-        loop {
-            std::hint::spin_loop();
+pub(crate) struct EventEnvelope {
+    sequence: AtomicU64,
+    slot: EventSlot,
+    published_at: AtomicU64,
+}
 
-            let current_event = self.event.load(Ordering::Acquire, &guard);
+impl EventEnvelope {
+    pub fn new() -> Self {
+        Self {
+            sequence: AtomicU64::new(0),
+            slot: EventSlot::new(),
+            published_at: AtomicU64::new(0),
+        }
+    }
 
-            match self
-                .event
-                .compare_and_set(current_event, event, Ordering::AcqRel, &guard)
-            {
-                Ok(_) => {
-                    self.sequence.store(sequence, Ordering::Release);
+    pub fn sequence(&self) -> u64 {
+        self.sequence.load(Ordering::Acquire)
+    }
 
-                    if !current_event.is_null() {
-                        unsafe {
-                            guard.defer_destroy(current_event);
-                        }
-                    }
+    /// Get the [`Metadata::published_at`] timestamp of the event, without pinning the event.
+    pub fn published_at(&self) -> u64 {
+        self.published_at.load(Ordering::Relaxed)
+    }
 
-                    loop {
-                        match self.num_waiting.compare_exchange(
-                            0,
-                            0,
-                            Ordering::Acquire,
-                            Ordering::Relaxed,
-                        ) {
-                            Ok(_) => break,
-
-                            Err(_) => {
-                                let num_waiting = self.num_waiting.load(Ordering::Acquire);
-
-                                for alerter_opt in
-                                    self.subscribers.pop_iter().take(num_waiting as usize)
-                                {
-                                    if let Some(alerter) = alerter_opt {
-                                        alerter.alert();
-                                    }
-                                }
-                            }
-                        }
-                    }
+    /// Reads the event in the envelope if it is of type `T`.
+    ///
+    /// The returned [`EventRead`] holds its own reference count on the event, so it doesn't keep
+    /// overwritten events from being reclaimed.
+    pub fn read<T: 'static>(&self) -> Option<EventRead<T>> {
+        self.slot.read(self.sequence())
+    }
 
-                    break;
-                }
+    /// Calls `f` with the event in the envelope, whatever its type.
+    pub fn with_event<R>(&self, f: impl FnOnce(&Event) -> R) -> Option<R> {
+        self.slot.with_event(f)
+    }
 
-                Err(cas_err) => {
-                    event = cas_err.new;
-                }
-            }
-        }
+    /// Overwrites the event in the envelope, along with the [`Metadata`] it was published with.
+    pub(crate) fn overwrite(&self, sequence: u64, event: AnyEvent, metadata: Metadata) {
+        let published_at = metadata.published_at;
+        self.slot.replace(Event {
+            type_id: event.type_id,
+            data: event.data,
+            metadata,
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::current(),
+        });
+
+        self.published_at.store(published_at, Ordering::Relaxed);
+        self.sequence.store(sequence, Ordering::Release);
     }
 }
 
@@ -167,7 +431,7 @@ mod tests {
     #[test]
     fn event_read() {
         let e = EventEnvelope::new();
-        let r = unsafe { e.read::<String>() };
+        let r = e.read::<String>();
         assert!(r.is_none());
     }
 
@@ -175,27 +439,31 @@ mod tests {
     fn event_read_deref() {
         let e = EventEnvelope::new();
         let i: usize = 5555;
-        e.overwrite(1, i);
+        e.overwrite(1, AnyEvent::new(i), Metadata::default());
 
-        let r = unsafe { e.read::<usize>() }.unwrap();
+        let r = e.read::<usize>().unwrap();
         assert_eq!(5555, *r);
     }
 
     #[test]
     fn event_read_as_ref() {
         let e = EventEnvelope::new();
-        e.overwrite(1, String::from("test"));
+        e.overwrite(1, AnyEvent::new(String::from("test")), Metadata::default());
 
-        let r = unsafe { e.read::<String>() }.unwrap();
+        let r = e.read::<String>().unwrap();
         assert!(r.eq("test"));
     }
 
     #[test]
     fn event_overwrite() {
         let e = EventEnvelope::new();
-        e.overwrite(1, String::from("Hello world!"));
+        e.overwrite(
+            1,
+            AnyEvent::new(String::from("Hello world!")),
+            Metadata::default(),
+        );
 
-        let readable_event = unsafe { e.read::<String>() };
+        let readable_event = e.read::<String>();
         assert!(readable_event.is_some());
 
         let read_msg = &*readable_event.unwrap();
@@ -203,9 +471,13 @@ mod tests {
 
         assert!(expected_msg.eq(read_msg));
 
-        e.overwrite(1, String::from("Bye Felicia!"));
+        e.overwrite(
+            1,
+            AnyEvent::new(String::from("Bye Felicia!")),
+            Metadata::default(),
+        );
 
-        let another_readable_event = unsafe { e.read::<String>() };
+        let another_readable_event = e.read::<String>();
         assert!(another_readable_event.is_some());
 
         let another_read_msg = &*another_readable_event.unwrap();
@@ -215,4 +487,215 @@ mod tests {
 
         assert!(expected_msg.eq(read_msg));
     }
+
+    fn event<T: 'static + Send + Sync>(data: T) -> Event {
+        Event {
+            type_id: TypeId::of::<T>(),
+            data: Arc::new(data),
+            metadata: Metadata::default(),
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::none(),
+        }
+    }
+
+    /// An event that counts how many events were dropped, to observe when they are reclaimed.
+    struct Tracked(usize, Arc<AtomicU64>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.1.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+
+    /// Flushes the epoch until `drops` reaches `n`, or gives up.
+    fn reclaim(drops: &AtomicU64, n: u64) -> u64 {
+        for _ in 0..10_000 {
+            if drops.load(Ordering::Acquire) >= n {
+                break;
+            }
+
+            pin().flush();
+            std::thread::yield_now();
+        }
+
+        drops.load(Ordering::Acquire)
+    }
+
+    // The slot tests below also run under Miri. Crossbeam's epoch needs tree borrows, and leaks
+    // its own garbage queue at exit, so they are run with:
+    //
+    //   MIRIFLAGS="-Zmiri-tree-borrows -Zmiri-permissive-provenance -Zmiri-ignore-leaks" \
+    //     cargo +nightly miri test --lib event::
+
+    #[test]
+    fn slot_read() {
+        let slot = EventSlot::new();
+        assert!(slot.read::<usize>(1).is_none());
+
+        slot.replace(event(5555_usize));
+        assert!(slot.read::<String>(1).is_none());
+        assert_eq!(5555, *slot.read::<usize>(1).unwrap());
+    }
+
+    #[test]
+    fn slot_read_outlives_replace() {
+        let slot = EventSlot::new();
+        slot.replace(event(String::from("Hello world!")));
+
+        let r = slot.read::<String>(1).unwrap();
+
+        for i in 2..=64 {
+            slot.replace(event(i.to_string()));
+            pin().flush();
+        }
+
+        assert_eq!("Hello world!", *r);
+        assert_eq!("64", *slot.read::<String>(64).unwrap());
+    }
+
+    #[test]
+    fn slot_read_outlives_slot() {
+        let slot = EventSlot::new();
+        slot.replace(event(String::from("Hello world!")));
+
+        let r = slot.read::<String>(1).unwrap();
+        drop(slot);
+
+        assert_eq!("Hello world!", *r);
+    }
+
+    #[test]
+    fn held_slot_read_does_not_stall_reclamation() {
+        let drops = Arc::new(AtomicU64::new(0));
+
+        let slot = EventSlot::new();
+        slot.replace(event(Tracked(1, drops.clone())));
+        let held = slot.read::<Tracked>(1).unwrap();
+
+        for i in 2..=8 {
+            slot.replace(event(Tracked(i, drops.clone())));
+        }
+
+        // Every replaced event but the held one is reclaimed, and the held one is still intact
+        assert_eq!(6, reclaim(&drops, 6));
+        assert_eq!(1, held.0);
+
+        // The held event is dropped once both the slot and the reader let go of it
+        drop(held);
+        drop(slot);
+        assert_eq!(8, reclaim(&drops, 8));
+    }
+
+    #[test]
+    fn slot_read_can_be_sent() {
+        let slot = EventSlot::new();
+        slot.replace(event(5555_usize));
+
+        let r = slot.read::<usize>(1).unwrap();
+        let duplicate = r.duplicate();
+
+        let thread = std::thread::spawn(move || *r);
+        slot.replace(event(1234_usize));
+
+        assert_eq!(5555, thread.join().unwrap());
+        assert_eq!(5555, *duplicate);
+    }
+
+    #[test]
+    fn slot_read_into_shared() {
+        let slot = EventSlot::new();
+        slot.replace(event(String::from("shared")));
+
+        let r = slot.read::<String>(1).unwrap();
+        assert_eq!("shared", r.cloned());
+
+        let shared = r.into_shared();
+        let other = shared.clone();
+
+        // The shared event outlives being replaced in the slot
+        slot.replace(event(String::from("replaced")));
+
+        let thread = std::thread::spawn(move || other.len());
+        assert_eq!(6, thread.join().unwrap());
+        assert_eq!("shared", *shared.into_arc());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn receive_span_follows_from_publish_span() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::{Arc, Mutex};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+        use tracing_core::span::Current;
+
+        #[derive(Default)]
+        struct FollowsFrom {
+            next_id: AtomicU64,
+            spans: Mutex<Vec<&'static Metadata<'static>>>,
+            entered: Mutex<Vec<Id>>,
+            pairs: Arc<Mutex<Vec<(u64, u64)>>>,
+        }
+
+        impl tracing::Subscriber for FollowsFrom {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+                self.spans.lock().unwrap().push(attrs.metadata());
+                Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, span: &Id, follows: &Id) {
+                let pair = (span.into_u64(), follows.into_u64());
+                self.pairs.lock().unwrap().push(pair);
+            }
+
+            fn event(&self, _: &Event<'_>) {}
+
+            fn enter(&self, span: &Id) {
+                self.entered.lock().unwrap().push(span.clone());
+            }
+
+            fn exit(&self, _: &Id) {
+                self.entered.lock().unwrap().pop();
+            }
+
+            fn current_span(&self) -> Current {
+                match self.entered.lock().unwrap().last() {
+                    Some(id) => {
+                        let metadata = self.spans.lock().unwrap()[id.into_u64() as usize - 1];
+                        Current::new(id.clone(), metadata)
+                    }
+
+                    None => Current::none(),
+                }
+            }
+        }
+
+        let recorder = FollowsFrom::default();
+        let pairs = recorder.pairs.clone();
+
+        tracing::subscriber::with_default(recorder, || {
+            let e = EventEnvelope::new();
+
+            let publish_span = tracing::trace_span!("publish");
+            publish_span.in_scope(|| {
+                e.overwrite(
+                    1,
+                    AnyEvent::new(5555_usize),
+                    crate::event::Metadata::default(),
+                )
+            });
+
+            let r = e.read::<usize>().unwrap();
+            let receive_id = r.span().id().unwrap().into_u64();
+            let publish_id = publish_span.id().unwrap().into_u64();
+
+            assert_eq!(vec![(receive_id, publish_id)], *pairs.lock().unwrap());
+        });
+    }
 }
@@ -0,0 +1,183 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+type EvictionCallback = Arc<dyn Fn(&Eviction) + Send + Sync>;
+
+/// Declares when a lagging subscriber should be evicted from the event-bus.
+///
+/// Evicted subscribers no longer gate publishers, and receive an
+/// [`Error::Evicted`](crate::Error::Evicted) error on their next read. This prevents a single
+/// stuck subscriber from halting all publishers under
+/// [`WaitStrategy::AllSubscribers`](crate::WaitStrategy::AllSubscribers).
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let policy = EvictionPolicy::new()
+///     .max_lag(64)
+///     .max_stall(Duration::from_secs(1))
+///     .on_evict(|eviction| println!("evicted subscriber {}", eviction.subscriber_id));
+///
+/// let eventbus = Eventador::builder().eviction_policy(policy).build()?;
+/// ```
+///
+#[derive(Clone, Default)]
+pub struct EvictionPolicy {
+    max_lag: Option<u64>,
+    max_stall: Option<Duration>,
+    on_evict: Option<EvictionCallback>,
+}
+
+impl EvictionPolicy {
+    /// Creates a policy that doesn't evict any subscribers until limits are set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evict subscribers that have more than `n` published events left unread.
+    pub fn max_lag(mut self, n: u64) -> Self {
+        self.max_lag = Some(n);
+        self
+    }
+
+    /// Evict subscribers that keep a publisher waiting for longer than `duration`.
+    pub fn max_stall(mut self, duration: Duration) -> Self {
+        self.max_stall = Some(duration);
+        self
+    }
+
+    /// Registers a callback that is invoked whenever a subscriber is evicted.
+    ///
+    /// The callback runs on the publisher that evicted the subscriber, so it should return quickly.
+    pub fn on_evict(mut self, callback: impl Fn(&Eviction) + Send + Sync + 'static) -> Self {
+        self.on_evict = Some(Arc::new(callback));
+        self
+    }
+
+    pub(crate) fn lag_limit(&self) -> Option<u64> {
+        self.max_lag
+    }
+
+    pub(crate) fn should_evict(
+        &self,
+        lag: u64,
+        stalled_for: Option<Duration>,
+    ) -> Option<EvictionReason> {
+        if let Some(max_lag) = self.max_lag {
+            if lag > max_lag {
+                return Some(EvictionReason::Lagged);
+            }
+        }
+
+        if let (Some(max_stall), Some(stalled_for)) = (self.max_stall, stalled_for) {
+            if stalled_for > max_stall {
+                return Some(EvictionReason::Stalled);
+            }
+        }
+
+        None
+    }
+
+    pub(crate) fn notify(&self, eviction: &Eviction) {
+        if let Some(callback) = &self.on_evict {
+            callback(eviction);
+        }
+    }
+}
+
+/// The reason a subscriber was evicted from the event-bus.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EvictionReason {
+    /// The subscriber had more unread events than the policy's maximum lag.
+    Lagged,
+
+    /// The subscriber kept a publisher waiting for longer than the policy's maximum stall.
+    Stalled,
+}
+
+/// Details about an evicted subscriber, passed to the [`EvictionPolicy::on_evict`] callback.
+#[derive(Debug, Clone)]
+pub struct Eviction {
+    /// The id of the evicted subscriber.
+    pub subscriber_id: u64,
+
+    /// The sequence of the next event the subscriber would have read.
+    pub sequence: u64,
+
+    /// The number of published events the subscriber had not yet read.
+    pub lag: u64,
+
+    /// Why the subscriber was evicted.
+    pub reason: EvictionReason,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Eventador, EvictionPolicy, EvictionReason, WaitStrategy};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn evicts_stalled_subscriber() {
+        let evicted_id = Arc::new(AtomicU64::new(0));
+        let callback_id = evicted_id.clone();
+
+        let policy = EvictionPolicy::new()
+            .max_stall(Duration::from_millis(100))
+            .on_evict(move |eviction| {
+                assert_eq!(EvictionReason::Stalled, eviction.reason);
+                callback_id.store(eviction.subscriber_id, Ordering::Release);
+            });
+
+        let eventbus = Eventador::builder()
+            .capacity(2)
+            .eviction_policy(policy)
+            .build()
+            .unwrap();
+
+        let stuck = eventbus.subscribe::<usize>();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        let publish_bus = eventbus.clone();
+        let publish_thread = std::thread::spawn(move || {
+            for i in 1..=4_usize {
+                publish_bus.publish(i);
+            }
+        });
+
+        for i in 1..=4_usize {
+            assert_eq!(i, *subscriber.recv().unwrap());
+        }
+
+        publish_thread.join().unwrap();
+        assert_eq!(stuck.id(), evicted_id.load(Ordering::Acquire));
+        assert_eq!(Some(Error::Evicted), stuck.recv().err());
+    }
+
+    #[test]
+    fn evicts_lagging_subscriber() {
+        let policy = EvictionPolicy::new().max_lag(2);
+        let eventbus = Eventador::builder()
+            .capacity(8)
+            .wait_strategy(WaitStrategy::NoWait)
+            .eviction_policy(policy)
+            .build()
+            .unwrap();
+
+        let lagging = eventbus.subscribe::<usize>();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        for i in 1..=4_usize {
+            eventbus.publish(i);
+            assert_eq!(i, *subscriber.recv().unwrap());
+        }
+
+        assert_eq!(Some(Error::Evicted), lagging.recv().err());
+
+        eventbus.publish(5_usize);
+        assert_eq!(5, *subscriber.recv().unwrap());
+    }
+}
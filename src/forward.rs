@@ -0,0 +1,491 @@
+use crate::error::Error;
+use crate::event::Metadata;
+use crate::ring_buffer::RingBuffer;
+use crate::sequence::gating_sequence::GatingSequence;
+use crate::wait_strategy::WaitStrategy;
+use crate::waiter::Alerter;
+use crate::Eventador;
+use crossbeam::sync::{Parker, Unparker};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How long the forwarding thread waits at a time, before checking whether it was stopped.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub(crate) type ForwardFn<A, B> = Box<dyn Fn(&A) -> Option<B> + Send>;
+
+struct ForwarderState {
+    stopped: AtomicBool,
+    forwarded: AtomicU64,
+}
+
+/// A handle to the thread that forwards events from one event-bus to another, created by
+/// [`Eventador::forward`], [`Eventador::forward_filter`] or [`Eventador::forward_map`].
+///
+/// Forwarding continues until the handle is stopped, until the forwarder is evicted from the
+/// source for lagging behind, or until every handle to either the source or the target event-bus
+/// is dropped. Dropping the handle itself detaches the thread rather than
+/// stopping it, so that event-buses can be wired together once and then left alone.
+///
+/// The forwarding thread doesn't hold on to either event-bus, so event-buses that forward to each
+/// other are still closed once their last handles are dropped. Events are never forwarded to an
+/// event-bus they already passed through, so they don't circle between such event-buses.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let parent = Eventador::new(1024)?;
+/// let child = Eventador::new(64)?;
+///
+/// let forwarder = parent.forward::<Order>(&child)?;
+/// parent.publish(order);
+///
+/// forwarder.stop();
+/// ```
+///
+pub struct Forwarder {
+    id: u64,
+    state: Arc<ForwarderState>,
+    unparker: Unparker,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Forwarder {
+    pub(crate) fn spawn<A, B>(
+        source: &Eventador,
+        target: &Eventador,
+        f: ForwardFn<A, B>,
+    ) -> Result<Self, Error>
+    where
+        A: 'static,
+        B: 'static + Send + Sync,
+    {
+        target
+            .ring
+            .require_multi_producer("events can't be forwarded to a single-producer event-bus")?;
+
+        let ring = &source.ring;
+
+        let next = ring.sequencer().get() + 1;
+        let sequence = ring.sequencer().register_gating_sequence(next);
+        let id = sequence.id();
+
+        let state = Arc::new(ForwarderState {
+            stopped: AtomicBool::new(false),
+            forwarded: AtomicU64::new(0),
+        });
+
+        let parker = Parker::new();
+        let unparker = parker.unparker().clone();
+
+        let mut forwarding = Forwarding {
+            source: Arc::downgrade(ring),
+            source_id: ring.id(),
+            target: Arc::downgrade(&target.ring),
+            target_id: target.ring.id(),
+            sequence,
+            state: state.clone(),
+            parker,
+            next,
+            f,
+        };
+
+        let thread = std::thread::Builder::new()
+            .name(String::from("eventador-forward"))
+            .spawn(move || forwarding.run())
+            .map_err(|err| Error::Spawn(err.to_string()))?;
+
+        Ok(Self {
+            id,
+            state,
+            unparker,
+            thread: Some(thread),
+        })
+    }
+
+    /// Get the id of the forwarder, which it is identified by in the source event-bus's
+    /// [`Stats`](crate::Stats) and [`Hooks`](crate::Hooks), and in the lag recorded on the target
+    /// event-bus.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Get the number of events that were forwarded so far.
+    pub fn forwarded(&self) -> u64 {
+        self.state.forwarded.load(Ordering::Acquire)
+    }
+
+    /// Returns whether events are still being forwarded, which is no longer the case once the
+    /// forwarder was evicted from the source.
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Stops forwarding, and waits for the forwarding thread to exit.
+    ///
+    /// An event that is waiting on the target's subscribers when the forwarder is stopped is not
+    /// forwarded.
+    pub fn stop(mut self) {
+        self.state.stopped.store(true, Ordering::Release);
+        self.unparker.unpark();
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+struct Forwarding<A, B> {
+    source: Weak<RingBuffer>,
+    source_id: u64,
+    target: Weak<RingBuffer>,
+    target_id: u64,
+    sequence: Arc<GatingSequence>,
+    state: Arc<ForwarderState>,
+    parker: Parker,
+
+    /// The sequence of the next event to forward.
+    next: u64,
+    f: ForwardFn<A, B>,
+}
+
+impl<A: 'static, B: 'static + Send + Sync> Forwarding<A, B> {
+    fn run(&mut self) {
+        while !self.is_stopped() {
+            let ring = match self.source.upgrade() {
+                Some(ring) => ring,
+                None => return,
+            };
+
+            let envelope = ring
+                .get_envelope(self.next)
+                .expect("ring buffer was not pre-populated with empty event envelopes");
+
+            let envelope_sequence = envelope.sequence();
+            if self.next == envelope_sequence {
+                let target_id = self.target_id;
+                let f = &self.f;
+
+                // Events are not sent back to an event-bus they already passed through, which keeps
+                // events from circling between event-buses that forward to each other
+                let forwarded = envelope
+                    .with_event(|event| {
+                        let metadata = &event.metadata;
+                        let visited = metadata.origin == target_id
+                            || metadata
+                                .route
+                                .as_ref()
+                                .is_some_and(|route| route.contains(&target_id));
+
+                        if visited {
+                            return None;
+                        }
+
+                        event
+                            .data
+                            .downcast_ref::<A>()
+                            .and_then(f)
+                            .map(|forwarded| (forwarded, metadata.clone()))
+                    })
+                    .flatten();
+
+                // The event was overwritten while it was being read
+                if envelope.sequence() != self.next {
+                    continue;
+                }
+
+                ring.record_receive(&self.sequence, self.next, &envelope);
+                self.next = self.sequence.increment() + 1;
+                drop(ring);
+
+                if let Some((event, metadata)) = forwarded {
+                    self.publish(event, metadata);
+                }
+            } else if self.next < envelope_sequence {
+                // Publisher has overwritten events before they could be forwarded, which the
+                // target's subscribers miss out on as well
+                let overwritten =
+                    ring.record_overwritten(self.sequence.id(), self.next, envelope_sequence);
+                if let Some(target) = self.target.upgrade() {
+                    target.record_lag(self.sequence.id(), overwritten);
+                }
+
+                self.sequence.set(envelope_sequence);
+                self.next = envelope_sequence;
+            } else {
+                drop(ring);
+
+                let alerter = Alerter::unparker(&self.parker);
+                self.sequence
+                    .waiter()
+                    .wait(&envelope, self.next, alerter, || {
+                        self.parker.park_timeout(POLL_INTERVAL)
+                    });
+            }
+        }
+    }
+
+    /// Forwarding stops once the forwarder is stopped or evicted from the source, or once every
+    /// handle to the source or the target is dropped.
+    fn is_stopped(&self) -> bool {
+        self.state.stopped.load(Ordering::Acquire)
+            || self.sequence.is_evicted()
+            || self.source.strong_count() == 0
+            || self.target.strong_count() == 0
+    }
+
+    fn publish(&self, event: B, metadata: Metadata) {
+        // Claims are retried so that the forwarder can be stopped while the target is full, but
+        // never for less than the target's own wait, so that lossy claims are not given up on
+        let (target, sequence) = loop {
+            // The target is only held on to while claiming, so that it can be dropped while the
+            // forwarder waits on it
+            let target = match self.target.upgrade() {
+                Some(target)
+                    if !self.state.stopped.load(Ordering::Acquire)
+                        && !self.sequence.is_evicted() =>
+                {
+                    target
+                }
+                _ => return,
+            };
+
+            let timeout = match target.sequencer().wait_strategy() {
+                WaitStrategy::WaitForDuration(wait) => std::cmp::max(wait, POLL_INTERVAL),
+                _ => POLL_INTERVAL,
+            };
+
+            if let Ok(sequence) = target.next_timeout(timeout) {
+                break (target, sequence);
+            }
+        };
+
+        // The route of the event is extended with the source, so that it isn't forwarded back
+        let route = metadata
+            .route
+            .iter()
+            .flat_map(|route| route.iter().copied())
+            .chain(std::iter::once(self.source_id))
+            .collect();
+
+        // The origin and correlation of the event are kept, so that requests can be replied to
+        // across event-buses
+        let metadata = Metadata {
+            origin: metadata.origin,
+            route: Some(route),
+            request_id: metadata.request_id,
+            reply_to: metadata.reply_to,
+            ..Metadata::default()
+        };
+
+        target.write_with(sequence, event, metadata);
+        self.state.forwarded.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+impl<A, B> Drop for Forwarding<A, B> {
+    fn drop(&mut self) {
+        // Stop gating the source's publishers once forwarding stops
+        if let Some(ring) = self.source.upgrade() {
+            ring.sequencer().deregister_gating_sequence(&self.sequence);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Eventador, EvictionPolicy, Hooks, WaitStrategy};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn wait_for(f: impl Fn() -> bool) {
+        while !f() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn forwards_filtered_and_mapped_events() {
+        let parent = Eventador::new(4).unwrap();
+        let child = Eventador::new(16).unwrap();
+
+        let usizes = child.subscribe::<usize>();
+        let strings = child.subscribe::<String>();
+
+        let _forward = parent
+            .forward_filter::<usize, _>(&child, |i| i % 2 == 0)
+            .unwrap();
+        let _map = parent
+            .forward_map::<u32, String, _>(&child, |i| i.to_string())
+            .unwrap();
+
+        let publish_bus = parent.clone();
+        let publish_thread = std::thread::spawn(move || {
+            for i in 1..=10 {
+                publish_bus.publish(i as usize);
+                publish_bus.publish(i as u32);
+            }
+        });
+
+        // The parent is smaller than the number of events, so publishing waits on the forwarders
+        for i in 1..=5 {
+            assert_eq!(i * 2, *usizes.recv().unwrap());
+        }
+        for i in 1..=10 {
+            assert_eq!(i.to_string(), *strings.recv().unwrap());
+        }
+
+        publish_thread.join().unwrap();
+    }
+
+    #[test]
+    fn does_not_forward_events_back_to_their_origin() {
+        let a = Eventador::new(16).unwrap();
+        let b = Eventador::new(16).unwrap();
+
+        let a_to_b = a.forward::<usize>(&b).unwrap();
+        let b_to_a = b.forward::<usize>(&a).unwrap();
+
+        let subscriber = a.subscribe::<usize>();
+        a.publish(1_usize);
+        b.publish(2_usize);
+
+        wait_for(|| a_to_b.forwarded() == 1 && b_to_a.forwarded() == 1);
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert_eq!(2, *subscriber.recv().unwrap());
+
+        // Neither event made it around the loop a second time
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(2, a.stats().published_count::<usize>());
+        assert_eq!(2, b.stats().published_count::<usize>());
+        assert_eq!(1, a_to_b.forwarded());
+        assert_eq!(1, b_to_a.forwarded());
+    }
+
+    struct LagCounter(AtomicU64);
+
+    impl Hooks for LagCounter {
+        fn on_lag(&self, _subscriber_id: u64, skipped: u64) {
+            self.0.fetch_add(skipped, Ordering::AcqRel);
+        }
+    }
+
+    #[test]
+    fn propagates_lag_and_close() {
+        let lagged = Arc::new(LagCounter(AtomicU64::new(0)));
+
+        let parent = Eventador::builder()
+            .capacity(4)
+            .wait_strategy(WaitStrategy::NoWait)
+            .build()
+            .unwrap();
+        let child = Eventador::builder()
+            .capacity(1024)
+            .hooks(lagged.clone())
+            .build()
+            .unwrap();
+
+        let forwarder = parent.forward::<usize>(&child).unwrap();
+
+        // The forwarder can't keep up with a publisher that doesn't wait on it
+        for i in 0..1000 {
+            parent.publish(i as usize);
+        }
+
+        wait_for(|| parent.stats().subscribers.iter().all(|s| s.lag == 0));
+
+        // The events overwritten before they were forwarded are lag on the child as well
+        let lag_drops = parent.stats().lag_drops;
+        assert!(lag_drops > 0);
+        assert_eq!(lag_drops, lagged.0.load(Ordering::Acquire));
+        assert!(forwarder.forwarded() + lag_drops <= 1000);
+
+        // Closing the parent stops forwarding
+        drop(parent);
+        wait_for(|| !forwarder.is_running());
+    }
+
+    #[test]
+    fn closes_event_buses_that_forward_to_each_other() {
+        let a = Eventador::new(16).unwrap();
+        let b = Eventador::new(16).unwrap();
+
+        let a_to_b = a.forward::<usize>(&b).unwrap();
+        let b_to_a = b.forward::<usize>(&a).unwrap();
+
+        let a_ring = Arc::downgrade(&a.ring);
+        let b_ring = Arc::downgrade(&b.ring);
+        drop(a);
+        drop(b);
+
+        // Neither forwarding thread keeps the other's source alive
+        wait_for(|| !a_to_b.is_running() && !b_to_a.is_running());
+        assert_eq!(0, a_ring.strong_count());
+        assert_eq!(0, b_ring.strong_count());
+    }
+
+    #[test]
+    fn stops_forwarding() {
+        let parent = Eventador::new(4).unwrap();
+        let child = Eventador::new(4).unwrap();
+
+        let forwarder = parent.forward::<usize>(&child).unwrap();
+        forwarder.stop();
+
+        // The stopped forwarder no longer gates the parent
+        for i in 0..10 {
+            parent.publish(i as usize);
+        }
+        assert_eq!(0, child.stats().published_count::<usize>());
+    }
+
+    #[test]
+    fn does_not_forward_events_around_cycles() {
+        let a = Eventador::new(16).unwrap();
+        let b = Eventador::new(16).unwrap();
+        let c = Eventador::new(16).unwrap();
+
+        let a_to_b = a.forward::<usize>(&b).unwrap();
+        let b_to_c = b.forward::<usize>(&c).unwrap();
+        let c_to_b = c.forward::<usize>(&b).unwrap();
+
+        let subscriber = b.subscribe::<usize>();
+        a.publish(1_usize);
+
+        // The cycle doesn't include the origin, but the event already passed through B
+        wait_for(|| a_to_b.forwarded() == 1 && b_to_c.forwarded() == 1);
+        std::thread::sleep(Duration::from_millis(50));
+
+        assert_eq!(0, c_to_b.forwarded());
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert_eq!(1, b_to_c.forwarded());
+    }
+
+    #[test]
+    fn stops_once_evicted() {
+        let source = Eventador::builder()
+            .capacity(4)
+            .eviction_policy(EvictionPolicy::new().max_stall(Duration::from_millis(10)))
+            .build()
+            .unwrap();
+        let target = Eventador::new(4).unwrap();
+
+        // The target's subscriber never reads, so the forwarder falls behind on the source
+        let _stalled = target.subscribe::<usize>();
+        let forwarder = source.forward::<usize>(&target).unwrap();
+
+        for i in 1..=10_usize {
+            source.publish(i);
+        }
+
+        wait_for(|| !forwarder.is_running());
+        assert_eq!(4, forwarder.forwarded());
+    }
+}
@@ -1,5 +1,9 @@
 pub(crate) mod publisher;
 pub(crate) mod subscriber;
+pub(crate) mod throttle;
+pub(crate) mod window;
 
 pub use publisher::{AsyncPublisher, PublishError};
 pub use subscriber::AsyncSubscriber;
+pub use throttle::{AsyncDebouncedSubscriber, AsyncThrottledSubscriber};
+pub use window::AsyncWindowedSubscriber;
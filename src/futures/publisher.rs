@@ -1,30 +1,68 @@
+use crate::error::Error;
+use crate::event::AnyEvent;
 use crate::ring_buffer::RingBuffer;
-use async_stream::stream;
+use crate::sequence::claim::Claim;
 use futures::{
     task::{Context, Poll},
-    Sink, Stream,
+    Sink,
 };
+use std::collections::VecDeque;
 use std::pin::Pin;
 use std::sync::Arc;
 
 /// An error thrown by the [`AsyncPublisher`] as part of the `Sink` trait implementation.
 ///
-#[derive(Debug, Clone)]
-pub struct PublishError;
+/// The underlying [`Error`] that caused the failure can be accessed through
+/// [`PublishError::cause`], or as the error's `source`.
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PublishError {
+    cause: Error,
+}
+
+impl PublishError {
+    /// Get the [`Error`] that caused the event to not be published.
+    pub fn cause(&self) -> &Error {
+        &self.cause
+    }
+}
+
+impl From<Error> for PublishError {
+    fn from(cause: Error) -> Self {
+        Self { cause }
+    }
+}
+
+impl From<PublishError> for Error {
+    fn from(err: PublishError) -> Self {
+        err.cause
+    }
+}
 
 impl std::fmt::Display for PublishError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "async-publisher could not get an overwriteable envelope from the ring"
+            "async-publisher could not get an overwriteable envelope from the ring: {}",
+            self.cause
         )
     }
 }
 
+impl std::error::Error for PublishError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.cause)
+    }
+}
+
 /// A handle to asynchronously publish to the event-bus.
 ///
 /// Implements the [`Sink`] trait to asynchronously publish a stream of events to the event-bus.
 ///
+/// Without a type parameter, the publisher is untyped: events of any type can be published with
+/// [`AsyncPublisher::publish`], or sent through the [`Sink`] as [`AnyEvent`]s. Either way, events
+/// are published as their own type.
+///
 /// # Example
 ///
 /// Basic usage:
@@ -37,65 +75,120 @@ impl std::fmt::Display for PublishError {
 /// publisher.send(i).await?;
 /// ```
 ///
-pub struct AsyncPublisher<T> {
+/// Publishing events of different types:
+///
+/// ```ignore
+/// let mut publisher: AsyncPublisher = eventbus.async_publisher(10);
+///
+/// publisher.publish(1234_usize).await?;
+/// publisher.publish(String::from("eventador")).await?;
+/// ```
+///
+/// # Cancellation
+///
+/// A sequence is only claimed within the same poll that writes its event, so dropping any of the
+/// publisher's futures, or the publisher itself, never leaves a claimed sequence unpublished for
+/// subscribers to stall on. Buffered events are published in the order they were sent.
+///
+/// - `flush`: dropping the future stops publishing. The events that were published stay
+///   published, and the rest stay buffered until the next flush.
+/// - `send`: dropping the future before the event was buffered drops the event unpublished. Once
+///   it is buffered, the event is published by the next flush.
+/// - `send_all`: the events taken from the stream are buffered as with `send`, except for the
+///   one taken while the buffer was full, which is dropped unpublished along with the future.
+/// - [`AsyncPublisher::publish`]: dropping the future before the event was published drops the
+///   event unpublished, and leaves any events that were not flushed yet buffered.
+///
+/// Events that are still buffered when the publisher is dropped are not published, so the
+/// publisher should be closed before it is dropped.
+///
+pub struct AsyncPublisher<T = AnyEvent> {
     ring: Arc<RingBuffer>,
     buffer_size: usize,
-    events: Vec<T>,
-    sequence_stream: Pin<Box<dyn Stream<Item = u64> + Send + Sync>>,
+    events: VecDeque<AnyEvent>,
+    claim: Option<Claim>,
+    _marker: std::marker::PhantomData<fn(T)>,
 }
 
 impl<T: 'static + Send + Sync + Unpin> AsyncPublisher<T> {
     pub(crate) fn new(ring: Arc<RingBuffer>, buffer: usize) -> Self {
         let buffer = if buffer == 0 { buffer + 1 } else { buffer };
 
-        let stream_ring = ring.clone();
-        let stream = Box::pin(stream! {
-            loop {
-                yield stream_ring.async_next().await;
-            }
-        });
-
         Self {
             ring,
             buffer_size: buffer,
-            events: Vec::with_capacity(buffer),
-            sequence_stream: stream,
+            events: VecDeque::with_capacity(buffer),
+            claim: None,
+            _marker: std::marker::PhantomData,
         }
     }
+
+    /// Publish an event of any type on the event-bus, once the events that were buffered before
+    /// it are published.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut publisher: AsyncPublisher = eventbus.async_publisher(1);
+    ///
+    /// publisher.publish(1234_usize).await?;
+    /// publisher.publish(String::from("eventador")).await?;
+    /// ```
+    ///
+    pub async fn publish<E: 'static + Send + Sync>(
+        &mut self,
+        event: E,
+    ) -> Result<(), PublishError> {
+        futures::future::poll_fn(|cx| Pin::new(&mut *self).poll_flush(cx)).await?;
+
+        let sequence = self.ring.sequencer().async_next_from(1).await?;
+        self.ring.write(sequence, event);
+
+        Ok(())
+    }
 }
 
 impl<T: 'static + Send + Sync + Unpin> Sink<T> for AsyncPublisher<T> {
     type Error = PublishError;
 
-    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         if self.events.len() >= self.buffer_size {
-            Poll::Pending
-        } else {
-            Poll::Ready(Ok(()))
+            // Makes room by publishing the buffered events, which wakes the task once it can
+            return self.poll_flush(cx);
         }
+
+        Poll::Ready(Ok(()))
     }
 
     fn start_send(mut self: Pin<&mut Self>, event: T) -> Result<(), Self::Error> {
-        self.events.push(event);
+        self.events.push_back(AnyEvent::new(event));
 
         Ok(())
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        while !self.events.is_empty() {
-            match self.sequence_stream.as_mut().poll_next(cx) {
-                Poll::Ready(Some(sequence)) => {
-                    if let Some(event) = self.events.pop() {
-                        let envelope = self
-                            .ring
-                            .get_envelope(sequence)
-                            .expect("ring buffer was not pre-populated with empty event envelopes");
-
-                        envelope.overwrite(sequence, event);
+        let this = &mut *self;
+
+        while !this.events.is_empty() {
+            let claim = this.claim.get_or_insert_with(|| Claim::new(1));
+
+            match claim.poll_claim(this.ring.sequencer(), cx) {
+                // The claimed sequence is written before returning, so it is never left unpublished
+                Poll::Ready(Ok(sequence)) => {
+                    this.claim = None;
+
+                    if let Some(event) = this.events.pop_front() {
+                        this.ring.write(sequence, event);
                     }
                 }
 
-                Poll::Ready(None) => return Poll::Ready(Err(PublishError)),
+                Poll::Ready(Err(err)) => {
+                    this.claim = None;
+                    return Poll::Ready(Err(err.into()));
+                }
 
                 Poll::Pending => return Poll::Pending,
             }
@@ -112,5 +205,92 @@ impl<T: 'static + Send + Sync + Unpin> Sink<T> for AsyncPublisher<T> {
 
 #[cfg(test)]
 mod tests {
-    // use crate::async_publisher::*;
+    use crate::{AnyEvent, AsyncPublisher, Eventador, SinkExt};
+    use futures::{stream, FutureExt};
+    use std::time::Duration;
+
+    #[async_std::test]
+    async fn publishes_events_of_any_type() {
+        let eventbus = Eventador::new(4).unwrap();
+        let numbers = eventbus.subscribe::<usize>();
+        let strings = eventbus.subscribe::<String>();
+        let mut publisher: AsyncPublisher = eventbus.async_publisher(4);
+
+        publisher.publish(1_usize).await.unwrap();
+        publisher.publish(String::from("two")).await.unwrap();
+
+        publisher.send(AnyEvent::new(3_usize)).await.unwrap();
+        publisher
+            .send(AnyEvent::new(String::from("four")))
+            .await
+            .unwrap();
+
+        assert_eq!(1, *numbers.recv().unwrap());
+        assert_eq!(3, *numbers.recv().unwrap());
+        assert_eq!("two", *strings.recv().unwrap());
+        assert_eq!("four", *strings.recv().unwrap());
+
+        // Events are counted as their own type rather than as `AnyEvent`s
+        let published = eventbus.stats().published;
+        assert_eq!(2, published[&std::any::TypeId::of::<usize>()].count);
+        assert!(!published.contains_key(&std::any::TypeId::of::<AnyEvent>()));
+    }
+
+    #[async_std::test]
+    async fn cancelled_flush_leaves_nothing_claimed() {
+        let eventbus = Eventador::new(2).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+        let mut publisher: AsyncPublisher<usize> = eventbus.async_publisher(4);
+
+        for i in 1..=3_usize {
+            publisher.feed(i).now_or_never().unwrap().unwrap();
+        }
+
+        // The flush publishes what fits in the ring, and is dropped while it waits for the rest
+        assert!(publisher.flush().now_or_never().is_none());
+        assert_eq!(2, eventbus.ring.sequencer().get());
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+        publisher.flush().await.unwrap();
+
+        assert_eq!(2, *subscriber.recv().unwrap());
+        assert_eq!(3, *subscriber.recv().unwrap());
+
+        // Dropping a publisher while it waits leaves no unpublished sequence behind either
+        publisher.feed(4).now_or_never().unwrap().unwrap();
+        publisher.feed(5).now_or_never().unwrap().unwrap();
+        publisher.feed(6).now_or_never().unwrap().unwrap();
+        assert!(publisher.flush().now_or_never().is_none());
+        drop(publisher);
+
+        assert_eq!(4, *subscriber.recv().unwrap());
+        assert_eq!(5, *subscriber.recv().unwrap());
+
+        eventbus.publish(7_usize);
+        assert_eq!(7, *subscriber.recv().unwrap());
+    }
+
+    #[async_std::test]
+    async fn full_buffer_waits_for_subscribers() {
+        let eventbus = Eventador::new(2).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+        let mut publisher: AsyncPublisher<usize> = eventbus.async_publisher(1);
+
+        let subscriber_thread = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+
+            (1..=4_usize)
+                .map(|_| *subscriber.recv().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        // The buffer fills up while the ring is full, so the publisher must be woken once the
+        // subscriber catches up
+        let mut events = stream::iter((1..=4_usize).map(Ok));
+        let sent =
+            async_std::future::timeout(Duration::from_secs(5), publisher.send_all(&mut events));
+        sent.await.unwrap().unwrap();
+
+        assert_eq!(vec![1, 2, 3, 4], subscriber_thread.join().unwrap());
+    }
 }
@@ -1,23 +1,23 @@
-use crate::alertable::Alertable;
+use crate::error::Error;
 use crate::event::EventRead;
-use crate::ring_buffer::{EventWrapper, RingBuffer};
-use crate::sequence::Sequence;
-use crate::WaitStrategy;
-use futures::task::{Context, Poll, Waker};
+use crate::futures::throttle::{AsyncDebouncedSubscriber, AsyncThrottledSubscriber};
+use crate::futures::window::AsyncWindowedSubscriber;
+use crate::latency::{self, LatencyHistogram, LatencyRecorder};
+use crate::ring_buffer::RingBuffer;
+use crate::sequence::gating_sequence::GatingSequence;
+use crate::waiter::Alerter;
+use crate::window::Window;
+use futures::task::{Context, Poll};
 use futures::Stream;
 use std::pin::Pin;
 use std::sync::Arc;
-
-impl Alertable for Waker {
-    fn alert(&self) {
-        self.wake_by_ref();
-    }
-}
+use std::time::{Duration, Instant};
 
 /// A handle to subscribe to events and receive them asynchronously.
 ///
 /// Implements the [`Stream`] trait to offer subscribed events from the event-bus as an asynchronous
-/// stream.
+/// stream. If the subscriber is evicted from the event-bus for lagging, the stream yields an
+/// [`Error::Evicted`] error and then ends.
 ///
 /// # Example
 ///
@@ -32,26 +32,26 @@ impl Alertable for Waker {
 /// let mut i: usize = 1234;
 /// publisher.send(i).await?;
 ///
-/// let mut msg = subscriber.recv().await.unwrap();
+/// let mut msg = subscriber.next().await.unwrap()?;
 /// assert_eq!(i, *msg);
 /// ```
 ///
-pub struct AsyncSubscriber<'a, T> {
+pub struct AsyncSubscriber<T> {
     ring: Arc<RingBuffer>,
-    sequence: Arc<Sequence>,
-    current_event: Option<EventWrapper>,
-    _marker: std::marker::PhantomData<&'a T>,
+    sequence: Arc<GatingSequence>,
+    terminated: bool,
+    _marker: std::marker::PhantomData<fn() -> T>,
 }
 
-impl<'a, T> AsyncSubscriber<'a, T>
+impl<T> AsyncSubscriber<T>
 where
     T: Send,
 {
-    pub(crate) fn new(ring: Arc<RingBuffer>, sequence: Arc<Sequence>) -> Self {
+    pub(crate) fn new(ring: Arc<RingBuffer>, sequence: Arc<GatingSequence>) -> Self {
         Self {
             ring,
             sequence,
-            current_event: None,
+            terminated: false,
             _marker: std::marker::PhantomData,
         }
     }
@@ -63,50 +63,280 @@ where
     pub fn sequence(&self) -> u64 {
         self.sequence.get()
     }
+
+    /// Get the id that identifies the [`AsyncSubscriber`] on the event-bus, such as in an
+    /// [`Eviction`](crate::Eviction).
+    pub fn id(&self) -> u64 {
+        self.sequence.id()
+    }
+
+    /// Get a snapshot of how long the events read by the [`AsyncSubscriber`] spent in the ring after
+    /// being published.
+    ///
+    /// Returns `None` unless latency tracking was enabled through
+    /// [`EventadorBuilder::track_latency`](crate::EventadorBuilder::track_latency).
+    pub fn latency_histogram(&self) -> Option<LatencyHistogram> {
+        self.sequence.latency().map(LatencyRecorder::snapshot)
+    }
+
+    /// Groups the events that the [`AsyncSubscriber`] receives into windows.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut subscriber = eventbus
+    ///     .async_subscriber::<usize>()
+    ///     .window(Window::tumbling(Duration::from_secs(1)));
+    ///
+    /// let per_second = subscriber.recv_fold(0, |sum, i| sum + i).await?;
+    /// ```
+    ///
+    pub fn window(self, window: Window) -> AsyncWindowedSubscriber<T>
+    where
+        T: 'static,
+    {
+        self.ring.timestamp_events();
+        AsyncWindowedSubscriber::new(self, window)
+    }
+
+    /// Only receives the last event of each burst, once no other event has followed it for `delay`.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut subscriber = eventbus
+    ///     .async_subscriber::<usize>()
+    ///     .debounce(Duration::from_millis(50));
+    ///
+    /// let settled = subscriber.next().await.unwrap()?;
+    /// ```
+    ///
+    pub fn debounce(self, delay: Duration) -> AsyncDebouncedSubscriber<T>
+    where
+        T: 'static,
+    {
+        AsyncDebouncedSubscriber::new(self, delay)
+    }
+
+    /// Receives at most one event per `interval`, skipping the events in between.
+    ///
+    /// The interval is the inverse of a rate, so `Duration::from_secs(1) / 10` receives at most
+    /// ten events per second. It is taken as an interval so that rates below one event per second,
+    /// such as one per minute, are exact.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut subscriber = eventbus
+    ///     .async_subscriber::<usize>()
+    ///     .throttle(Duration::from_secs(1));
+    ///
+    /// let first = subscriber.next().await.unwrap()?;
+    /// ```
+    ///
+    pub fn throttle(self, interval: Duration) -> AsyncThrottledSubscriber<T>
+    where
+        T: 'static,
+    {
+        AsyncThrottledSubscriber::new(self, interval)
+    }
+
+    /// Get the number of events the [`AsyncSubscriber`] skipped over because they were
+    /// overwritten.
+    pub(crate) fn lagged(&self) -> u64 {
+        self.sequence.lagged()
+    }
+
+    /// Get when the event the [`AsyncSubscriber`] last received was published, if it was
+    /// timestamped.
+    pub(crate) fn published_at(&self) -> Option<Instant> {
+        match self.sequence.received_published_at() {
+            0 => None,
+            published_at => Some(latency::to_instant(published_at)),
+        }
+    }
+
+    /// Moves the [`AsyncSubscriber`] to a specific sequence in the event-bus.
+    ///
+    /// The sequence must still be held in the ring, or be the next sequence to be published.
+    /// Publishers gate on the new position immediately, so moving forward frees up any slots they
+    /// were waiting on.
+    ///
+    /// Moving back races concurrent publishers as described on
+    /// [`Subscriber::seek`](crate::Subscriber::seek).
+    pub fn seek(&self, sequence: u64) -> Result<(), Error> {
+        self.ring.seek(&self.sequence, sequence)?;
+        self.stop_waiting();
+
+        Ok(())
+    }
+
+    /// Skips any unread events so that the next read is of the most recently published event.
+    ///
+    /// If the latest event is not of the subscribed type, the [`AsyncSubscriber`] will instead
+    /// receive the next event of that type to be published. Returns the new sequence of the
+    /// Subscriber.
+    pub fn skip_to_latest(&self) -> u64 {
+        let sequence = self.ring.skip_to_latest(&self.sequence);
+        self.stop_waiting();
+
+        sequence
+    }
+
+    /// Moves the [`AsyncSubscriber`] back by `n` sequences to re-read events that are still held
+    /// in the ring. Returns the new sequence of the Subscriber.
+    ///
+    /// Moving back races concurrent publishers as described on
+    /// [`Subscriber::seek`](crate::Subscriber::seek).
+    pub fn rewind(&self, n: u64) -> Result<u64, Error> {
+        let sequence = self.ring.rewind(&self.sequence, n)?;
+        self.stop_waiting();
+
+        Ok(sequence)
+    }
 }
 
-impl<'a, T: 'static> Stream for AsyncSubscriber<'a, T> {
-    type Item = EventRead<'a, T>;
+impl<T> AsyncSubscriber<T> {
+    fn stop_waiting(&self) {
+        self.sequence.waiter().cancel();
+    }
+}
+
+impl<T> Drop for AsyncSubscriber<T> {
+    fn drop(&mut self) {
+        self.stop_waiting();
+        self.ring
+            .sequencer()
+            .deregister_gating_sequence(&self.sequence);
+    }
+}
+
+impl<T: 'static> Stream for AsyncSubscriber<T> {
+    type Item = Result<EventRead<T>, Error>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         loop {
-            let sequence = self.sequence.get();
-            let envelope = if let Some(envelope) = self.current_event.take() {
-                envelope
-            } else {
-                let envelope = self
-                    .ring
-                    .get_envelope(sequence)
-                    .expect("ring buffer was not pre-populated with empty event envelopes")
-                    .clone();
+            if self.terminated {
+                return Poll::Ready(None);
+            } else if self.sequence.is_evicted() {
+                self.stop_waiting();
+                self.terminated = true;
+                return Poll::Ready(Some(Err(Error::Evicted)));
+            }
 
-                envelope.start_waiting();
-                envelope
-            };
+            let sequence = self.sequence.get();
+            let envelope = self
+                .ring
+                .get_envelope(sequence)
+                .expect("ring buffer was not pre-populated with empty event envelopes")
+                .clone();
 
             let envelope_sequence = envelope.sequence();
             if sequence == envelope_sequence {
-                let event_opt: Option<EventRead<T>> = unsafe { envelope.read() };
-                envelope.stop_waiting();
+                let event_opt: Option<EventRead<T>> = envelope.read();
+
+                if event_opt.is_some() {
+                    self.ring
+                        .record_receive(&self.sequence, sequence, &envelope);
+                }
 
                 self.sequence.increment();
                 if let Some(event) = event_opt {
-                    return Poll::Ready(Some(event));
+                    self.stop_waiting();
+                    return Poll::Ready(Some(Ok(event)));
                 }
             } else if sequence > envelope_sequence {
-                envelope.add_subscriber(Box::new(cx.waker().clone()));
-                self.current_event.replace(envelope);
-                return Poll::Pending;
-            } else {
-                // Publisher has overwritten an event that has not been read yet
-                match self.ring.wait_strategy() {
-                    WaitStrategy::AllSubscribers => unreachable!(),
+                // The waker stays registered until the next poll, and the envelope is checked again
+                // after registering it, so that the publisher can't miss it
+                let alerter = Alerter::Waker(cx.waker().clone());
+                self.sequence.waiter().wait_for(sequence, alerter);
 
-                    _ => {
-                        self.sequence.set(envelope_sequence);
-                    }
+                if envelope.sequence() < sequence {
+                    return Poll::Pending;
                 }
+            } else {
+                // Publisher has overwritten an event that has not been read yet, which can happen
+                // with lossy wait-strategies or after rewinding to an event that was being wrapped
+                self.sequence.set(envelope_sequence);
+
+                let overwritten =
+                    self.ring
+                        .record_overwritten(self.sequence.id(), sequence, envelope_sequence);
+                self.sequence.add_lagged(overwritten);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Eventador, StreamExt};
+    use futures::task::{noop_waker, Context};
+    use futures::Stream;
+    use std::pin::Pin;
+
+    #[async_std::test]
+    async fn moves_into_spawned_tasks() {
+        let eventbus = Eventador::new(4).unwrap();
+        let mut subscriber = eventbus.async_subscriber::<usize>();
+
+        // The subscriber doesn't borrow the event-bus, so it can outlive the handle it came from
+        let task =
+            async_std::task::spawn(async move { *subscriber.next().await.unwrap().unwrap() });
+        eventbus.publish(1_usize);
+        drop(eventbus);
+
+        assert_eq!(1, task.await);
+    }
+
+    #[async_std::test]
+    async fn skip_to_latest_and_rewind() {
+        let eventbus = Eventador::new(4).unwrap();
+        let mut subscriber = eventbus.async_subscriber::<usize>();
+
+        for i in 1..=3_usize {
+            eventbus.publish(i);
+        }
+
+        assert_eq!(3, subscriber.skip_to_latest());
+        assert_eq!(3, *subscriber.next().await.unwrap().unwrap());
+
+        assert_eq!(2, subscriber.rewind(2).unwrap());
+        assert_eq!(2, *subscriber.next().await.unwrap().unwrap());
+
+        assert!(subscriber.seek(6).is_err());
+        assert!(subscriber.seek(1).is_ok());
+        assert_eq!(1, *subscriber.next().await.unwrap().unwrap());
+    }
+
+    #[async_std::test]
+    async fn publishers_progress_past_abandoned_waits() {
+        let eventbus = Eventador::new(4).unwrap();
+        let mut abandoned = eventbus.async_subscriber::<usize>();
+        let mut subscriber = eventbus.async_subscriber::<usize>();
+
+        // The waiting subscriber is never polled again, but publishers don't wait for it to
+        // acknowledge the wake-up
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(Pin::new(&mut abandoned).poll_next(&mut cx).is_pending());
+
+        eventbus.publish(1_usize);
+        assert_eq!(1, *subscriber.next().await.unwrap().unwrap());
+
+        drop(abandoned);
+        for i in 2..=9_usize {
+            eventbus.publish(i);
+            assert_eq!(i, *subscriber.next().await.unwrap().unwrap());
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::error::Error;
+use crate::event::EventRead;
+use crate::futures::subscriber::AsyncSubscriber;
+use futures::task::{Context, Poll};
+use futures::{Future, Stream, StreamExt};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// An [`AsyncSubscriber`] that only receives the last of a burst of events, created by
+/// [`AsyncSubscriber::debounce`].
+///
+/// Implements the [`Stream`] trait to offer the debounced events as an asynchronous stream. If the
+/// subscriber is evicted from the event-bus for lagging, the pending event is yielded, followed by
+/// an [`Error::Evicted`] error, and then the stream ends.
+///
+/// Events are only read off the ring while the stream is polled, so while it isn't, the subscriber
+/// holds back publishers like any other.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(1024)?;
+/// let mut subscriber = eventbus
+///     .async_subscriber::<SearchQuery>()
+///     .debounce(Duration::from_millis(300));
+///
+/// let query = subscriber.next().await.unwrap()?;
+/// ```
+///
+pub struct AsyncDebouncedSubscriber<T> {
+    subscriber: AsyncSubscriber<T>,
+    delay: Duration,
+    pending: Option<(Instant, EventRead<T>)>,
+    sleep: Option<(Instant, Sleep)>,
+    error: Option<Error>,
+    terminated: bool,
+}
+
+impl<T: 'static + Send> AsyncDebouncedSubscriber<T> {
+    pub(crate) fn new(subscriber: AsyncSubscriber<T>, delay: Duration) -> Self {
+        Self {
+            subscriber,
+            delay,
+            pending: None,
+            sleep: None,
+            error: None,
+            terminated: false,
+        }
+    }
+
+    /// Get the [`AsyncSubscriber`] that the events are received from.
+    pub fn subscriber(&self) -> &AsyncSubscriber<T> {
+        &self.subscriber
+    }
+
+    /// Polls the timer for when the pending event has gone the delay without being superseded.
+    fn poll_deadline(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let deadline = match self.pending.as_ref() {
+            Some((at, _)) => *at + self.delay,
+            None => {
+                self.sleep = None;
+                return Poll::Pending;
+            }
+        };
+
+        let now = Instant::now();
+        if now >= deadline {
+            self.sleep = None;
+            return Poll::Ready(());
+        }
+
+        // The timer is replaced whenever a newer event pushes the deadline back
+        if self.sleep.as_ref().map(|(at, _)| *at) != Some(deadline) {
+            let sleep: Sleep = Box::pin(async_std::task::sleep(deadline - now));
+            self.sleep = Some((deadline, sleep));
+        }
+
+        match self.sleep.as_mut() {
+            Some((_, sleep)) => sleep.as_mut().poll(cx),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T: 'static + Send> Stream for AsyncDebouncedSubscriber<T> {
+    type Item = Result<EventRead<T>, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if this.error.is_some() || this.terminated {
+                if let Some((_, event)) = this.pending.take() {
+                    return Poll::Ready(Some(Ok(event)));
+                } else if let Some(err) = this.error.take() {
+                    return Poll::Ready(Some(Err(err)));
+                }
+
+                return Poll::Ready(None);
+            }
+
+            match this.subscriber.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(event))) => this.pending = Some((Instant::now(), event)),
+                Poll::Ready(Some(Err(err))) => this.error = Some(err),
+                Poll::Ready(None) => this.terminated = true,
+
+                Poll::Pending => match this.poll_deadline(cx) {
+                    Poll::Ready(()) => {
+                        if let Some((_, event)) = this.pending.take() {
+                            return Poll::Ready(Some(Ok(event)));
+                        }
+                    }
+                    Poll::Pending => return Poll::Pending,
+                },
+            }
+        }
+    }
+}
+
+/// An [`AsyncSubscriber`] that receives at most one event per interval, created by
+/// [`AsyncSubscriber::throttle`].
+///
+/// Implements the [`Stream`] trait to offer the throttled events as an asynchronous stream. If the
+/// subscriber is evicted from the event-bus for lagging, the stream yields an [`Error::Evicted`]
+/// error and then ends.
+///
+/// Events are only read off the ring while the stream is polled, so while it isn't, the subscriber
+/// holds back publishers like any other. The events that are waiting when the stream is polled
+/// within the interval are skipped over at once.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(1024)?;
+/// let mut subscriber = eventbus
+///     .async_subscriber::<Alert>()
+///     .throttle(Duration::from_secs(60));
+///
+/// let alert = subscriber.next().await.unwrap()?;
+/// ```
+///
+pub struct AsyncThrottledSubscriber<T> {
+    subscriber: AsyncSubscriber<T>,
+    interval: Duration,
+    next: Option<Instant>,
+}
+
+impl<T: 'static + Send> AsyncThrottledSubscriber<T> {
+    pub(crate) fn new(subscriber: AsyncSubscriber<T>, interval: Duration) -> Self {
+        Self {
+            subscriber,
+            interval,
+            next: None,
+        }
+    }
+
+    /// Get the [`AsyncSubscriber`] that the events are received from.
+    pub fn subscriber(&self) -> &AsyncSubscriber<T> {
+        &self.subscriber
+    }
+}
+
+impl<T: 'static + Send> Stream for AsyncThrottledSubscriber<T> {
+    type Item = Result<EventRead<T>, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            // Every event that is waiting within the interval would be skipped anyway
+            if this.next.is_some_and(|next| Instant::now() < next) {
+                this.subscriber.skip_to_latest();
+            }
+
+            match this.subscriber.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(event))) => {
+                    let now = Instant::now();
+
+                    if this.next.is_none_or(|next| now >= next) {
+                        this.next = Some(now + this.interval);
+                        return Poll::Ready(Some(Ok(event)));
+                    }
+                }
+
+                poll => return poll,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Eventador, StreamExt};
+    use std::time::Duration;
+
+    #[async_std::test]
+    async fn debounces_and_throttles_asynchronously() {
+        let eventbus = Eventador::new(4).unwrap();
+        let mut debounced = eventbus
+            .async_subscriber::<usize>()
+            .debounce(Duration::from_millis(30));
+        let mut throttled = eventbus
+            .async_subscriber::<usize>()
+            .throttle(Duration::from_secs(60));
+
+        for i in 1..=3_usize {
+            eventbus.publish(i);
+        }
+
+        assert_eq!(1, *throttled.next().await.unwrap().unwrap());
+        assert_eq!(3, *debounced.next().await.unwrap().unwrap());
+
+        // The skipped events were read off the ring
+        let throttled_next =
+            async_std::future::timeout(Duration::from_millis(30), throttled.next());
+        assert!(throttled_next.await.is_err());
+        assert_eq!(4, throttled.subscriber().sequence());
+    }
+}
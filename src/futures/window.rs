@@ -0,0 +1,167 @@
+use crate::error::Error;
+use crate::event::EventRead;
+use crate::futures::subscriber::AsyncSubscriber;
+use crate::window::{Window, Windower};
+use futures::task::{Context, Poll};
+use futures::{Future, Stream, StreamExt};
+use std::pin::Pin;
+use std::time::Instant;
+
+type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// An [`AsyncSubscriber`] that receives events in windows, created by
+/// [`AsyncSubscriber::window`].
+///
+/// Implements the [`Stream`] trait to offer the windows as an asynchronous stream. If the
+/// subscriber is evicted from the event-bus for lagging, the open window is yielded, followed by an
+/// [`Error::Evicted`] error, and then the stream ends.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(1024)?;
+/// let mut subscriber = eventbus
+///     .async_subscriber::<usize>()
+///     .window(Window::session(Duration::from_millis(100)));
+///
+/// let burst = subscriber.next().await.unwrap()?;
+/// ```
+///
+pub struct AsyncWindowedSubscriber<T> {
+    subscriber: AsyncSubscriber<T>,
+    windower: Windower<T>,
+    sleep: Option<(Instant, Sleep)>,
+    error: Option<Error>,
+    terminated: bool,
+}
+
+impl<T: 'static + Send> AsyncWindowedSubscriber<T> {
+    pub(crate) fn new(subscriber: AsyncSubscriber<T>, window: Window) -> Self {
+        Self {
+            subscriber,
+            windower: Windower::new(window),
+            sleep: None,
+            error: None,
+            terminated: false,
+        }
+    }
+
+    /// Asynchronously read the next window of events from the event-bus, and aggregate it with
+    /// `f` starting from `init`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the subscriber was evicted from the event-bus for lagging, or
+    /// [`Error::Closed`] once the stream has ended.
+    ///
+    pub async fn recv_fold<A>(&mut self, init: A, f: impl FnMut(A, &T) -> A) -> Result<A, Error> {
+        let window = self.next().await.ok_or(Error::Closed)??;
+        Ok(window.iter().map(|event| &**event).fold(init, f))
+    }
+
+    /// Get the [`AsyncSubscriber`] that the windows are received from.
+    pub fn subscriber(&self) -> &AsyncSubscriber<T> {
+        &self.subscriber
+    }
+
+    /// Polls the timer for when the open window closes by time, if it does.
+    fn poll_deadline(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let deadline = match self.windower.deadline() {
+            Some(deadline) => deadline,
+            None => {
+                self.sleep = None;
+                return Poll::Pending;
+            }
+        };
+
+        let now = Instant::now();
+        if now >= deadline {
+            self.sleep = None;
+            return Poll::Ready(());
+        }
+
+        // The timer is replaced whenever the deadline moves, such as when a session is extended
+        if self.sleep.as_ref().map(|(at, _)| *at) != Some(deadline) {
+            let sleep: Sleep = Box::pin(async_std::task::sleep(deadline - now));
+            self.sleep = Some((deadline, sleep));
+        }
+
+        match self.sleep.as_mut() {
+            Some((_, sleep)) => sleep.as_mut().poll(cx),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T: 'static + Send> Stream for AsyncWindowedSubscriber<T> {
+    type Item = Result<Vec<EventRead<T>>, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if let Some(window) = this.windower.pop() {
+                return Poll::Ready(Some(Ok(window)));
+            } else if let Some(err) = this.error.take() {
+                return Poll::Ready(Some(Err(err)));
+            } else if this.terminated {
+                return Poll::Ready(None);
+            }
+
+            let lagged = this.subscriber.lagged();
+
+            match this.subscriber.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(event))) => {
+                    let lagged = this.subscriber.lagged() != lagged;
+                    let at = this.subscriber.published_at().unwrap_or_else(Instant::now);
+                    this.windower.push(at, event, lagged);
+                }
+
+                Poll::Ready(Some(Err(err))) => {
+                    this.windower.flush();
+                    this.error = Some(err);
+                }
+
+                Poll::Ready(None) => {
+                    this.windower.flush();
+                    this.terminated = true;
+                }
+
+                Poll::Pending => match this.poll_deadline(cx) {
+                    Poll::Ready(()) => this.windower.expire(Instant::now()),
+                    Poll::Pending => return Poll::Pending,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Eventador, StreamExt, Window};
+    use std::time::Duration;
+
+    #[async_std::test]
+    async fn windows_asynchronously() {
+        let eventbus = Eventador::new(16).unwrap();
+        let mut counted = eventbus
+            .async_subscriber::<usize>()
+            .window(Window::tumbling_count(2));
+        let mut sessions = eventbus
+            .async_subscriber::<usize>()
+            .window(Window::session(Duration::from_millis(30)));
+
+        for i in 1..=4_usize {
+            eventbus.publish(i);
+        }
+
+        let window = counted.next().await.unwrap().unwrap();
+        assert_eq!(vec![1, 2], window.iter().map(|e| **e).collect::<Vec<_>>());
+        assert_eq!(7, counted.recv_fold(0, |sum, i| sum + i).await.unwrap());
+
+        // The session closes once no event has been received for the gap
+        assert_eq!(10, sessions.recv_fold(0, |sum, i| sum + i).await.unwrap());
+    }
+}
@@ -0,0 +1,426 @@
+use crate::error::Error;
+use crate::ring_buffer::RingBuffer;
+use crate::subscriber::Subscriber;
+use crate::wait_strategy::WaitStrategy;
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How long the handler thread waits at a time, before checking whether it was stopped.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Declares what a [`Handler`] does with an event that its handler function panicked on.
+///
+/// Either way, an event that could not be handled is published as a [`DeadLetter`] before the
+/// handler moves on to the next event.
+///
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum FailurePolicy {
+    /// *Default*: Dead-letter the event straight away.
+    #[default]
+    Skip,
+
+    /// Call the handler function on the event again, up to `attempts` times in total, sleeping
+    /// for `backoff` between attempts, before dead-lettering it.
+    Retry { attempts: u32, backoff: Duration },
+}
+
+/// An event that a [`Handler`] could not handle, published in its place so that failures can be
+/// subscribed to like any other event.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let dead_letters = eventbus.subscribe::<DeadLetter>();
+///
+/// let failed = dead_letters.recv()?;
+/// println!("{} at sequence {} failed: {}", failed.type_name, failed.sequence, failed.message);
+/// ```
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DeadLetter {
+    /// The id of the handler that could not handle the event.
+    pub handler_id: u64,
+
+    /// The sequence of the event on the event-bus it was published to.
+    pub sequence: u64,
+
+    /// The name of the event's type, as given by [`std::any::type_name`].
+    pub type_name: &'static str,
+
+    /// The number of times the handler function was called on the event.
+    pub attempts: u32,
+
+    /// The message that the handler function panicked with on its last attempt.
+    pub message: String,
+}
+
+/// Configures a [`Handler`] before it is spawned, created by [`Eventador::handler`].
+///
+/// The handler subscribes when the builder is created, so events published after that are handled
+/// once it is spawned.
+///
+/// [`Eventador::handler`]: crate::Eventador::handler
+///
+pub struct HandlerBuilder<T> {
+    ring: Arc<RingBuffer>,
+    subscriber: Subscriber<T>,
+    dead_letters: Arc<RingBuffer>,
+    policy: FailurePolicy,
+}
+
+impl<T: 'static + Send> HandlerBuilder<T> {
+    pub(crate) fn new(ring: Arc<RingBuffer>, subscriber: Subscriber<T>) -> Self {
+        Self {
+            dead_letters: ring.clone(),
+            ring,
+            subscriber,
+            policy: FailurePolicy::default(),
+        }
+    }
+
+    /// Sets what the handler does with events that its handler function panics on.
+    pub fn policy(mut self, policy: FailurePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Publishes [`DeadLetter`]s to another event-bus, rather than to the one the events are
+    /// handled from.
+    pub fn dead_letters(mut self, eventbus: &crate::Eventador) -> Self {
+        self.dead_letters = eventbus.ring.clone();
+        self
+    }
+
+    /// Spawns a thread that calls `f` on every event of type `T`.
+    ///
+    /// Panics in `f` are caught, but the process's panic hook still runs for each of them, so the
+    /// default hook prints every attempt that panicked. The handler thread is named
+    /// `eventador-handler`, which a custom hook can use to leave these panics to the
+    /// [`DeadLetter`]s instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if dead letters would be published to an event-bus with
+    /// [`ProducerType::Single`](crate::ProducerType::Single), since the handler thread publishes
+    /// them alongside the event-bus's own publisher, or [`Error::Spawn`] if the handler thread
+    /// could not be started.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let default_hook = std::panic::take_hook();
+    /// std::panic::set_hook(Box::new(move |info| {
+    ///     if std::thread::current().name() != Some("eventador-handler") {
+    ///         default_hook(info);
+    ///     }
+    /// }));
+    ///
+    /// let handler = eventbus.handler::<Order>().spawn(|order| fill(order))?;
+    /// ```
+    ///
+    pub fn spawn(self, f: impl FnMut(&T) + Send + 'static) -> Result<Handler, Error> {
+        self.dead_letters.require_multi_producer(
+            "dead letters can't be published to a single-producer event-bus",
+        )?;
+
+        let id = self.subscriber.id();
+
+        let state = Arc::new(HandlerState {
+            stopped: AtomicBool::new(false),
+            handled: AtomicU64::new(0),
+            dead_lettered: AtomicU64::new(0),
+        });
+
+        let mut handling = Handling {
+            ring: self.ring,
+            subscriber: self.subscriber,
+            dead_letters: self.dead_letters,
+            policy: self.policy,
+            state: state.clone(),
+            f: Box::new(f),
+        };
+
+        let thread = std::thread::Builder::new()
+            .name(String::from("eventador-handler"))
+            .spawn(move || handling.run())
+            .map_err(|err| Error::Spawn(err.to_string()))?;
+
+        Ok(Handler {
+            id,
+            state,
+            thread: Some(thread),
+        })
+    }
+}
+
+struct HandlerState {
+    stopped: AtomicBool,
+    handled: AtomicU64,
+    dead_lettered: AtomicU64,
+}
+
+/// A handle to the thread that calls a handler function on every event of a type, created by
+/// [`HandlerBuilder::spawn`].
+///
+/// A panic in the handler function is caught, so that the handler keeps reading and never holds
+/// back publishers under [`WaitStrategy::AllSubscribers`]. The event it panicked on is retried or
+/// skipped according to the [`FailurePolicy`], and then published as a [`DeadLetter`].
+///
+/// Handling continues until the handle is stopped or dropped.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(1024)?;
+///
+/// let handler = eventbus
+///     .handler::<Order>()
+///     .policy(FailurePolicy::Retry { attempts: 3, backoff: Duration::from_millis(10) })
+///     .spawn(|order| fill(order))?;
+///
+/// eventbus.publish(order);
+///
+/// handler.stop();
+/// ```
+///
+pub struct Handler {
+    id: u64,
+    state: Arc<HandlerState>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Handler {
+    /// Get the id of the handler, which it is identified by in the event-bus's
+    /// [`Stats`](crate::Stats) and [`Hooks`](crate::Hooks), and in its [`DeadLetter`]s.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Get the number of events that were handled without the handler function panicking.
+    pub fn handled(&self) -> u64 {
+        self.state.handled.load(Ordering::Acquire)
+    }
+
+    /// Get the number of events that were published as [`DeadLetter`]s.
+    pub fn dead_lettered(&self) -> u64 {
+        self.state.dead_lettered.load(Ordering::Acquire)
+    }
+
+    /// Returns whether events are still being handled.
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Stops handling events, and waits for the handler thread to exit once it is done with the
+    /// event it is handling.
+    pub fn stop(mut self) {
+        self.state.stopped.store(true, Ordering::Release);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Handler {
+    fn drop(&mut self) {
+        // The thread is not joined, so that a handle can be dropped from within the handler
+        self.state.stopped.store(true, Ordering::Release);
+    }
+}
+
+struct Handling<T> {
+    ring: Arc<RingBuffer>,
+    subscriber: Subscriber<T>,
+    dead_letters: Arc<RingBuffer>,
+    policy: FailurePolicy,
+    state: Arc<HandlerState>,
+    f: Box<dyn FnMut(&T) + Send>,
+}
+
+impl<T: 'static + Send> Handling<T> {
+    fn run(&mut self) {
+        while !self.state.stopped.load(Ordering::Acquire) {
+            let event = match self
+                .subscriber
+                .recv_until(Some(Instant::now() + POLL_INTERVAL))
+            {
+                Ok(Some(event)) => event,
+                Ok(None) => continue,
+                Err(_) => return,
+            };
+
+            // The subscriber has already moved past the event it returned
+            let sequence = self.subscriber.sequence() - 1;
+
+            let attempts = match self.policy {
+                FailurePolicy::Skip => 1,
+                FailurePolicy::Retry { attempts, .. } => std::cmp::max(attempts, 1),
+            };
+
+            let mut attempt = 0;
+            let failure = loop {
+                attempt += 1;
+
+                let f = &mut self.f;
+                match catch_unwind(AssertUnwindSafe(|| f(&event))) {
+                    Ok(()) => break None,
+                    Err(payload) if attempt >= attempts => break Some(payload),
+                    Err(_) => {
+                        if let FailurePolicy::Retry { backoff, .. } = self.policy {
+                            std::thread::sleep(backoff);
+                        }
+                    }
+                }
+            };
+
+            match failure {
+                None => {
+                    self.state.handled.fetch_add(1, Ordering::AcqRel);
+                }
+
+                Some(payload) => self.dead_letter(DeadLetter {
+                    handler_id: self.subscriber.id(),
+                    sequence,
+                    type_name: std::any::type_name::<T>(),
+                    attempts: attempt,
+                    message: panic_message(payload.as_ref()),
+                }),
+            }
+        }
+    }
+
+    fn dead_letter(&self, dead_letter: DeadLetter) {
+        // The failure is recorded on the event-bus the event was handled from
+        self.ring
+            .record_dead_letter(dead_letter.handler_id, dead_letter.sequence);
+        self.state.dead_lettered.fetch_add(1, Ordering::AcqRel);
+
+        // Claims are retried so that the handler can be stopped while the event-bus is full, but
+        // never for less than its own wait, so that lossy claims are not given up on
+        let ring = &self.dead_letters;
+        let timeout = match ring.sequencer().wait_strategy() {
+            WaitStrategy::WaitForDuration(wait) => std::cmp::max(wait, POLL_INTERVAL),
+            _ => POLL_INTERVAL,
+        };
+
+        let sequence = loop {
+            if self.state.stopped.load(Ordering::Acquire) {
+                return;
+            }
+
+            if let Ok(sequence) = ring.next_timeout(timeout) {
+                break sequence;
+            }
+        };
+
+        ring.write(sequence, dead_letter);
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        String::from(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("handler panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DeadLetter, Eventador, FailurePolicy};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn dead_letters_panicking_events() {
+        let eventbus = Eventador::new(4).unwrap();
+        let dead_letters = eventbus.subscribe::<DeadLetter>();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        let handler = eventbus
+            .handler::<usize>()
+            .spawn(|i| {
+                if *i == 2 {
+                    panic!("cannot handle {}", i);
+                }
+            })
+            .unwrap();
+
+        for i in 1..=3_usize {
+            eventbus.publish(i);
+        }
+
+        let failed = dead_letters.recv().unwrap();
+        assert_eq!(handler.id(), failed.handler_id);
+        assert_eq!(2, failed.sequence);
+        assert_eq!("usize", failed.type_name);
+        assert_eq!(1, failed.attempts);
+        assert_eq!("cannot handle 2", failed.message);
+
+        // The handler kept reading past the event it panicked on, so it doesn't hold back
+        // publishers
+        for i in 1..=3_usize {
+            assert_eq!(i, *subscriber.recv().unwrap());
+        }
+        eventbus.publish(4_usize);
+        assert_eq!(4, *subscriber.recv().unwrap());
+
+        handler.stop();
+        assert_eq!(1, eventbus.stats().dead_letters);
+    }
+
+    #[test]
+    fn retries_before_dead_lettering() {
+        let eventbus = Eventador::new(4).unwrap();
+        let dead_letter_bus = Eventador::new(4).unwrap();
+        let dead_letters = dead_letter_bus.subscribe::<DeadLetter>();
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let handler_calls = calls.clone();
+
+        let handler = eventbus
+            .handler::<usize>()
+            .policy(FailurePolicy::Retry {
+                attempts: 3,
+                backoff: Duration::from_millis(1),
+            })
+            .dead_letters(&dead_letter_bus)
+            .spawn(move |i| {
+                // The first event succeeds on its second attempt, the second one never does
+                if handler_calls.fetch_add(1, Ordering::AcqRel) != 1 || *i == 2 {
+                    panic!("flaky");
+                }
+            })
+            .unwrap();
+
+        eventbus.publish(1_usize);
+        eventbus.publish(2_usize);
+
+        let failed = dead_letters.recv().unwrap();
+        assert_eq!(2, failed.sequence);
+        assert_eq!(3, failed.attempts);
+        assert_eq!(5, calls.load(Ordering::Acquire));
+        assert_eq!(1, handler.handled());
+        assert_eq!(1, handler.dead_lettered());
+
+        handler.stop();
+        assert_eq!(1, eventbus.stats().dead_letters);
+        assert_eq!(0, dead_letter_bus.stats().dead_letters);
+    }
+}
@@ -0,0 +1,68 @@
+use std::any::TypeId;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Callbacks that the event-bus invokes as events move through it, to feed metrics or tracing.
+///
+/// Every method has an empty default implementation, so only the hooks of interest need to be
+/// implemented. Hooks are called inline on publishers and subscribers, and so should be cheap.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// struct PublishCounter(AtomicU64);
+///
+/// impl Hooks for PublishCounter {
+///     fn on_publish(&self, _sequence: u64, _type_id: TypeId) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let eventbus = Eventador::builder()
+///     .hooks(PublishCounter(AtomicU64::new(0)))
+///     .build()?;
+/// ```
+///
+pub trait Hooks: Send + Sync {
+    /// Called after an event has been written to the ring.
+    fn on_publish(&self, _sequence: u64, _type_id: TypeId) {}
+
+    /// Called after a publisher had to wait on lagging subscribers before it could claim a
+    /// sequence.
+    fn on_publisher_wait(&self, _waited: Duration) {}
+
+    /// Called after a subscriber reads an event of its subscribed type.
+    fn on_receive(&self, _subscriber_id: u64, _sequence: u64) {}
+
+    /// Called when a subscriber skips over events that were overwritten before it could read
+    /// them, with the number of them as counted in [`Stats::lag_drops`](crate::Stats::lag_drops).
+    fn on_lag(&self, _subscriber_id: u64, _skipped: u64) {}
+
+    /// Called when a [`Handler`](crate::Handler) gives up on the event at `sequence` and
+    /// dead-letters it.
+    fn on_dead_letter(&self, _handler_id: u64, _sequence: u64) {}
+}
+
+impl<H: Hooks + ?Sized> Hooks for Arc<H> {
+    fn on_publish(&self, sequence: u64, type_id: TypeId) {
+        (**self).on_publish(sequence, type_id)
+    }
+
+    fn on_publisher_wait(&self, waited: Duration) {
+        (**self).on_publisher_wait(waited)
+    }
+
+    fn on_receive(&self, subscriber_id: u64, sequence: u64) {
+        (**self).on_receive(subscriber_id, sequence)
+    }
+
+    fn on_lag(&self, subscriber_id: u64, skipped: u64) {
+        (**self).on_lag(subscriber_id, skipped)
+    }
+
+    fn on_dead_letter(&self, handler_id: u64, sequence: u64) {
+        (**self).on_dead_letter(handler_id, sequence)
+    }
+}
@@ -0,0 +1,805 @@
+use crate::error::Error;
+use crate::event::Metadata;
+use crate::registry::TypeRegistry;
+use crate::ring_buffer::RingBuffer;
+use crate::sequence::gating_sequence::GatingSequence;
+use crate::waiter::Alerter;
+use crossbeam::sync::Parker;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+const SEGMENT_EXTENSION: &str = "log";
+const FRAME_HEADER_LEN: usize = 8;
+
+/// How often the journal forces appended events to disk with `fsync`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FsyncPolicy {
+    /// Sync after appending each event.
+    EveryEvent,
+
+    /// *Default*: Sync once the journal has appended every event that was available to it, so that
+    /// a burst of events is synced together.
+    Batch,
+
+    /// Sync at most once per interval, trading the durability of the most recent events for
+    /// throughput.
+    Interval(Duration),
+}
+
+/// Configures a journal that appends every published event of a registered type to a segmented,
+/// append-only log on disk.
+///
+/// The journal is set through [`EventadorBuilder::journal`](crate::EventadorBuilder::journal),
+/// and its writer reads events like any other subscriber. It gates publishers, so no event is
+/// overwritten before it has been appended.
+///
+/// After a restart, [`Eventador::recover`](crate::Eventador::recover) replays the log into the
+/// event-bus so that subscribers can rebuild their state.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let journal = Journal::new("data/orders")
+///     .register::<Order>("order")
+///     .fsync(FsyncPolicy::Batch);
+///
+/// let eventbus = Eventador::builder().journal(journal).build()?;
+/// let subscriber = eventbus.subscribe::<Order>();
+///
+/// eventbus.recover("data/orders")?;
+/// ```
+///
+#[derive(Clone)]
+pub struct Journal {
+    path: PathBuf,
+    segment_size: u64,
+    fsync: FsyncPolicy,
+    replay_timeout: Duration,
+    registry: TypeRegistry,
+}
+
+impl Journal {
+    /// Creates a journal that stores its log segments in the directory at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            segment_size: 64 * 1024 * 1024,
+            fsync: FsyncPolicy::Batch,
+            replay_timeout: Duration::from_secs(1),
+            registry: TypeRegistry::new(),
+        }
+    }
+
+    /// Journals events of type `T` under a stable `name`, which identifies the type in the log
+    /// across restarts and builds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is already registered to a different type.
+    ///
+    pub fn register<T>(mut self, name: impl Into<String>) -> Self
+    where
+        T: 'static + Send + Sync + Serialize + DeserializeOwned,
+    {
+        self.registry = self.registry.register::<T>(name);
+        self
+    }
+
+    /// Journals events of every type in `registry`, encoded with its codec. This replaces any
+    /// types registered on the journal so far.
+    ///
+    /// The codec of a journal should not change between restarts, since events in the log are
+    /// decoded with the current one.
+    pub fn registry(mut self, registry: TypeRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Sets the size in bytes at which the journal starts appending to a new log segment.
+    pub fn segment_size(mut self, bytes: u64) -> Self {
+        self.segment_size = bytes;
+        self
+    }
+
+    /// Sets how often appended events are forced to disk.
+    pub fn fsync(mut self, policy: FsyncPolicy) -> Self {
+        self.fsync = policy;
+        self
+    }
+
+    /// Sets how long recovery waits on lagging subscribers for room to replay each event.
+    /// Defaults to one second.
+    ///
+    /// Recovery fails with [`Error::Timeout`] once a replayed event can't be published in time,
+    /// which keeps a subscriber that only drains the event-bus after recovery from deadlocking
+    /// it.
+    pub fn replay_timeout(mut self, timeout: Duration) -> Self {
+        self.replay_timeout = timeout;
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.segment_size == 0 {
+            return Err(Error::InvalidConfig(
+                "journal segment size must be greater than zero",
+            ));
+        }
+
+        if let FsyncPolicy::Interval(interval) = self.fsync {
+            if interval == Duration::from_secs(0) {
+                return Err(Error::InvalidConfig(
+                    "journal fsync interval must be greater than zero",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replays every event in the log segments at `path` into the ring, and returns the number of
+    /// events that were replayed.
+    ///
+    /// Events of types that are not registered are skipped, as is the rest of a segment after a
+    /// torn or corrupted frame. Each event waits on lagging subscribers for at most the replay
+    /// timeout.
+    pub(crate) fn recover(&self, ring: &RingBuffer, path: &Path) -> Result<u64, Error> {
+        let metadata = Metadata {
+            replayed: true,
+            ..Metadata::default()
+        };
+
+        let mut replayed = 0;
+        for (_, segment) in segments(path)? {
+            for frame in read_frames(&segment)? {
+                match self.registry.publish_timeout(
+                    ring,
+                    &frame.name,
+                    &frame.payload,
+                    metadata.clone(),
+                    self.replay_timeout,
+                ) {
+                    Ok(()) => replayed += 1,
+                    Err(Error::UnregisteredType(_)) => continue,
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        Ok(replayed)
+    }
+}
+
+fn io_error(err: std::io::Error) -> Error {
+    Error::Journal(err.to_string())
+}
+
+/// Lists the log segments in `path` by the index of their first frame, in order.
+fn segments(path: &Path) -> Result<Vec<(u64, PathBuf)>, Error> {
+    let mut segments = Vec::new();
+
+    for entry in std::fs::read_dir(path).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+
+        if let Some(first_index) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u64>().ok())
+        {
+            segments.push((first_index, path));
+        }
+    }
+
+    segments.sort_unstable();
+    Ok(segments)
+}
+
+struct Frame {
+    name: String,
+    payload: Vec<u8>,
+}
+
+impl Frame {
+    /// The number of bytes the frame takes up in its segment.
+    fn len(&self) -> u64 {
+        (FRAME_HEADER_LEN + 2 + self.name.len() + self.payload.len()) as u64
+    }
+}
+
+/// Reads the frames of a log segment, up to the first torn or corrupted frame.
+///
+/// Each frame is laid out as the length and CRC-32 of its body, followed by the body itself: the
+/// length of the type name, the type name, and the encoded event.
+fn read_frames(segment: &Path) -> Result<Vec<Frame>, Error> {
+    let mut bytes = Vec::new();
+    File::open(segment)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(io_error)?;
+
+    let mut frames = Vec::new();
+    let mut rest = bytes.as_slice();
+
+    while rest.len() >= FRAME_HEADER_LEN {
+        let len = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let crc = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]);
+
+        let body = match rest.get(FRAME_HEADER_LEN..FRAME_HEADER_LEN + len) {
+            Some(body) if len >= 2 && crc32(body) == crc => body,
+            _ => break,
+        };
+
+        let name_len = u16::from_le_bytes([body[0], body[1]]) as usize;
+        let name = match body.get(2..2 + name_len).map(std::str::from_utf8) {
+            Some(Ok(name)) => name.to_string(),
+            _ => break,
+        };
+
+        frames.push(Frame {
+            name,
+            payload: body[2 + name_len..].to_vec(),
+        });
+
+        rest = &rest[FRAME_HEADER_LEN + len..];
+    }
+
+    Ok(frames)
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in bytes {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+/// Appends frames to the newest log segment, starting a new segment once it is full.
+struct SegmentWriter {
+    dir: PathBuf,
+    file: BufWriter<File>,
+    size: u64,
+    max_size: u64,
+    next_index: u64,
+}
+
+impl SegmentWriter {
+    /// Opens the last segment for appending, after cutting off any torn or corrupted frames at its
+    /// end so that new frames follow the last valid one.
+    fn open(dir: &Path, max_size: u64) -> Result<Self, Error> {
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+
+        let (first_index, frames) = match segments(dir)?.pop() {
+            Some((first_index, path)) => (first_index, read_frames(&path)?),
+            None => (0, Vec::new()),
+        };
+
+        let size = frames.iter().map(Frame::len).sum();
+        let file = Self::create(dir, first_index)?;
+        file.get_ref()
+            .set_len(size)
+            .and_then(|_| file.get_ref().sync_data())
+            .map_err(io_error)?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file,
+            size,
+            max_size,
+            next_index: first_index + frames.len() as u64,
+        })
+    }
+
+    fn create(dir: &Path, first_index: u64) -> Result<BufWriter<File>, Error> {
+        let path = dir.join(format!("{:020}.{}", first_index, SEGMENT_EXTENSION));
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(BufWriter::new)
+            .map_err(io_error)
+    }
+
+    fn append(&mut self, name: &str, payload: &[u8]) -> Result<(), Error> {
+        if name.len() > u16::MAX as usize {
+            return Err(Error::Journal(format!(
+                "type name {:?} is longer than {} bytes",
+                name,
+                u16::MAX
+            )));
+        }
+
+        let mut body = Vec::with_capacity(2 + name.len() + payload.len());
+        body.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        body.extend_from_slice(name.as_bytes());
+        body.extend_from_slice(payload);
+
+        let frame_len = (FRAME_HEADER_LEN + body.len()) as u64;
+        if self.size > 0 && self.size + frame_len > self.max_size {
+            self.sync()?;
+            self.file = Self::create(&self.dir, self.next_index)?;
+            self.size = 0;
+        }
+
+        self.file
+            .write_all(&(body.len() as u32).to_le_bytes())
+            .and_then(|_| self.file.write_all(&crc32(&body).to_le_bytes()))
+            .and_then(|_| self.file.write_all(&body))
+            .map_err(io_error)?;
+
+        self.size += frame_len;
+        self.next_index += 1;
+
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), Error> {
+        self.file
+            .flush()
+            .and_then(|_| self.file.get_ref().sync_data())
+            .map_err(io_error)
+    }
+}
+
+/// The progress of a journal's writer, shared with the event-bus.
+pub(crate) struct JournalState {
+    synced: AtomicU64,
+    error: Mutex<Option<Error>>,
+}
+
+impl JournalState {
+    /// Records the error the writer stopped with, unless it already stopped with another.
+    fn fail(&self, err: Error) {
+        self.error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_or_insert(err);
+    }
+
+    /// Waits until every event published up to `sequence` has been synced to disk.
+    pub fn wait_for_sync(&self, sequence: u64) -> Result<(), Error> {
+        loop {
+            if let Some(err) = self
+                .error
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+            {
+                return Err(err);
+            }
+
+            if self.synced.load(Ordering::Acquire) >= sequence {
+                return Ok(());
+            }
+
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+/// Starts the writer thread of a journal, which appends events from the ring until every handle to
+/// the event-bus is dropped.
+pub(crate) fn start(journal: Journal, ring: &Arc<RingBuffer>) -> Result<Arc<JournalState>, Error> {
+    let segment = SegmentWriter::open(&journal.path, journal.segment_size)?;
+
+    let next = ring.sequencer().get() + 1;
+    let sequence = ring.sequencer().register_gating_sequence(next);
+
+    let state = Arc::new(JournalState {
+        synced: AtomicU64::new(next - 1),
+        error: Mutex::new(None),
+    });
+
+    let mut writer = JournalWriter {
+        journal,
+        segment,
+        ring: Arc::downgrade(ring),
+        sequence,
+        state: state.clone(),
+        parker: Parker::new(),
+        dirty: false,
+        last_sync: Instant::now(),
+    };
+
+    std::thread::Builder::new()
+        .name(String::from("eventador-journal"))
+        .spawn(move || writer.run())
+        .map_err(io_error)?;
+
+    Ok(state)
+}
+
+struct JournalWriter {
+    journal: Journal,
+    segment: SegmentWriter,
+    ring: Weak<RingBuffer>,
+    sequence: Arc<GatingSequence>,
+    state: Arc<JournalState>,
+    parker: Parker,
+    dirty: bool,
+    last_sync: Instant,
+}
+
+impl JournalWriter {
+    fn run(&mut self) {
+        let result = self.write_until_closed();
+
+        if let Err(err) = result.and_then(|_| self.sync()) {
+            self.state.fail(err);
+        }
+    }
+
+    fn write_until_closed(&mut self) -> Result<(), Error> {
+        while let Some(ring) = self.ring.upgrade() {
+            self.drain(&ring)?;
+
+            if self.dirty && self.sync_due() {
+                self.sync()?;
+            }
+
+            if !self.dirty {
+                self.state
+                    .synced
+                    .store(self.sequence.get() - 1, Ordering::Release);
+            }
+
+            let next = self.sequence.get();
+            let envelope = ring
+                .get_envelope(next)
+                .expect("ring buffer was not pre-populated with empty event envelopes");
+            drop(ring);
+
+            let alerter = Alerter::unparker(&self.parker);
+            self.sequence.waiter().wait(&envelope, next, alerter, || {
+                self.parker.park_timeout(self.wait_timeout())
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Appends every event that has been published so far.
+    fn drain(&mut self, ring: &RingBuffer) -> Result<(), Error> {
+        loop {
+            let sequence = self.sequence.get();
+            let envelope = ring
+                .get_envelope(sequence)
+                .expect("ring buffer was not pre-populated with empty event envelopes");
+
+            let envelope_sequence = envelope.sequence();
+            if sequence == envelope_sequence {
+                let journal = &self.journal;
+                let encoded = envelope
+                    .with_event(|event| {
+                        if event.metadata.replayed {
+                            None
+                        } else {
+                            journal.registry.encode_event(event).map(|encoded| {
+                                encoded.map(|(name, payload)| (name.to_string(), payload))
+                            })
+                        }
+                    })
+                    .flatten();
+
+                self.sequence.increment();
+
+                if let Some(encoded) = encoded {
+                    let (name, payload) = encoded?;
+                    self.segment.append(&name, &payload)?;
+                    self.dirty = true;
+
+                    if self.journal.fsync == FsyncPolicy::EveryEvent {
+                        self.sync()?;
+                    }
+                }
+            } else if sequence < envelope_sequence {
+                // Publisher has overwritten events before they could be journaled, which can only
+                // happen with lossy wait-strategies
+                ring.record_overwritten(self.sequence.id(), sequence, envelope_sequence);
+                self.sequence.set(envelope_sequence);
+            } else {
+                break;
+            }
+        }
+
+        if self.dirty && self.journal.fsync == FsyncPolicy::Batch {
+            self.sync()?;
+        }
+
+        Ok(())
+    }
+
+    fn sync_due(&self) -> bool {
+        match self.journal.fsync {
+            FsyncPolicy::Interval(interval) => self.last_sync.elapsed() >= interval,
+            _ => true,
+        }
+    }
+
+    fn wait_timeout(&self) -> Duration {
+        match self.journal.fsync {
+            FsyncPolicy::Interval(interval) if self.dirty => {
+                interval.saturating_sub(self.last_sync.elapsed())
+            }
+
+            // Wake up regularly to check whether the event-bus was dropped
+            _ => Duration::from_millis(100),
+        }
+    }
+
+    fn sync(&mut self) -> Result<(), Error> {
+        if self.dirty {
+            self.segment.sync()?;
+            self.dirty = false;
+            self.last_sync = Instant::now();
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for JournalWriter {
+    fn drop(&mut self) {
+        // Runs when the writer stops, including when it panics, so that nothing waits on it to sync
+        self.state
+            .fail(Error::Journal(String::from("journal writer stopped")));
+
+        // Stop gating publishers, since the journal can no longer keep up with them
+        if let Some(ring) = self.ring.upgrade() {
+            ring.sequencer().deregister_gating_sequence(&self.sequence);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Eventador, FsyncPolicy, Journal};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn journal_dir(test: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("eventador-journal-{}-{}", test, std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn recovers_journaled_events() {
+        let dir = journal_dir("recover");
+        let journal = Journal::new(&dir)
+            .register::<(String, u64)>("order")
+            .segment_size(64)
+            .fsync(FsyncPolicy::EveryEvent);
+
+        {
+            let eventbus = Eventador::builder()
+                .journal(journal.clone())
+                .build()
+                .unwrap();
+
+            for i in 1..=10 {
+                eventbus.publish((String::from("order"), i as u64));
+                eventbus.publish(i as usize);
+            }
+
+            eventbus.sync_journal().unwrap();
+        }
+
+        // Small segments roll over, so the log spans several files
+        assert!(std::fs::read_dir(&dir).unwrap().count() > 1);
+
+        let eventbus = Eventador::builder().journal(journal).build().unwrap();
+        let subscriber = eventbus.subscribe::<(String, u64)>();
+
+        assert_eq!(10, eventbus.recover(&dir).unwrap());
+        for i in 1..=10 {
+            assert_eq!(i, subscriber.recv().unwrap().1);
+        }
+
+        // Replayed events are not journaled again
+        eventbus.publish((String::from("order"), 11_u64));
+        eventbus.sync_journal().unwrap();
+        drop(subscriber);
+
+        let recovered = Eventador::builder()
+            .journal(Journal::new(&dir).register::<(String, u64)>("order"))
+            .build()
+            .unwrap();
+        assert_eq!(11, recovered.recover(&dir).unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn skips_torn_frames() {
+        let dir = journal_dir("torn");
+        let journal = Journal::new(&dir).register::<u64>("count");
+
+        {
+            let eventbus = Eventador::builder()
+                .journal(journal.clone())
+                .build()
+                .unwrap();
+
+            eventbus.publish(1_u64);
+            eventbus.publish(2_u64);
+            eventbus.sync_journal().unwrap();
+        }
+
+        let segment = std::fs::read_dir(&dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let bytes = std::fs::read(&segment).unwrap();
+        std::fs::write(&segment, &bytes[..bytes.len() - 1]).unwrap();
+
+        let eventbus = Eventador::builder().journal(journal).build().unwrap();
+        assert_eq!(1, eventbus.recover(&dir).unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn appends_after_torn_first_frame() {
+        let dir = journal_dir("torn-first");
+        let journal = Journal::new(&dir).register::<u64>("count").segment_size(1);
+
+        {
+            let eventbus = Eventador::builder()
+                .journal(journal.clone())
+                .build()
+                .unwrap();
+
+            eventbus.publish(1_u64);
+            eventbus.publish(2_u64);
+            eventbus.sync_journal().unwrap();
+        }
+
+        // Each event fills a segment, so the first frame of the last segment is torn
+        let mut segments: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        segments.sort();
+        assert_eq!(2, segments.len());
+
+        let last = segments.pop().unwrap();
+        let bytes = std::fs::read(&last).unwrap();
+        std::fs::write(&last, &bytes[..3]).unwrap();
+
+        {
+            let eventbus = Eventador::builder()
+                .journal(journal.clone())
+                .build()
+                .unwrap();
+
+            eventbus.publish(3_u64);
+            eventbus.sync_journal().unwrap();
+        }
+
+        let eventbus = Eventador::builder().journal(journal).build().unwrap();
+        let subscriber = eventbus.subscribe::<u64>();
+
+        assert_eq!(2, eventbus.recover(&dir).unwrap());
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert_eq!(3, *subscriber.recv().unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_long_type_names() {
+        let dir = journal_dir("long-name");
+        let journal = Journal::new(&dir).register::<u64>("n".repeat(u16::MAX as usize + 1));
+        let eventbus = Eventador::builder().journal(journal).build().unwrap();
+
+        eventbus.publish(1_u64);
+        assert!(matches!(eventbus.sync_journal(), Err(Error::Journal(_))));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn times_out_replaying_past_capacity() {
+        let dir = journal_dir("past-capacity");
+        let journal = Journal::new(&dir)
+            .register::<u64>("count")
+            .replay_timeout(Duration::from_millis(10));
+
+        {
+            let eventbus = Eventador::builder()
+                .journal(journal.clone())
+                .build()
+                .unwrap();
+
+            for i in 1..=6_u64 {
+                eventbus.publish(i);
+            }
+            eventbus.sync_journal().unwrap();
+        }
+
+        // A subscriber that only drains after recovery can't make room for the whole log
+        let eventbus = Eventador::builder()
+            .capacity(4)
+            .journal(journal.clone())
+            .build()
+            .unwrap();
+        let subscriber = eventbus.subscribe::<u64>();
+
+        assert_eq!(Err(Error::Timeout), eventbus.recover(&dir));
+        assert_eq!(1, *subscriber.recv().unwrap());
+
+        // While one that drains on another thread can
+        let eventbus = Eventador::builder()
+            .capacity(4)
+            .journal(journal)
+            .build()
+            .unwrap();
+        let subscriber = eventbus.subscribe::<u64>();
+
+        let drain = std::thread::spawn(move || {
+            for i in 1..=6_u64 {
+                assert_eq!(i, *subscriber.recv().unwrap());
+            }
+        });
+
+        assert_eq!(Ok(6), eventbus.recover(&dir));
+        drain.join().unwrap();
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    struct Unserializable;
+
+    impl Serialize for Unserializable {
+        fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            panic!("event can't be serialized");
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Unserializable {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            u64::deserialize(deserializer).map(|_| Unserializable)
+        }
+    }
+
+    #[test]
+    fn fails_sync_when_writer_panics() {
+        let dir = journal_dir("panic");
+        let journal = Journal::new(&dir).register::<Unserializable>("unserializable");
+        let eventbus = Eventador::builder().journal(journal).build().unwrap();
+
+        eventbus.publish(Unserializable);
+        assert!(matches!(eventbus.sync_journal(), Err(Error::Journal(_))));
+
+        // The writer no longer gates publishers once it stopped
+        for i in 1..=eventbus.stats().capacity {
+            eventbus.publish(i);
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
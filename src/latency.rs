@@ -0,0 +1,261 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Each power-of-two range of latencies is split into this many equal buckets, which bounds the
+/// relative error of a recorded latency to under 2%.
+const SUB_BUCKET_BITS: u32 = 7;
+const SUB_BUCKET_COUNT: u64 = 1 << SUB_BUCKET_BITS;
+const SUB_BUCKET_HALF: u64 = SUB_BUCKET_COUNT / 2;
+
+/// Latencies above roughly 68 seconds are recorded as 68 seconds.
+const MAX_TRACKABLE_NANOS: u64 = (1 << 36) - 1;
+const BUCKET_COUNT: usize = bucket_index(MAX_TRACKABLE_NANOS) + 1;
+
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+/// Get the current time in nanoseconds since the first timestamp was taken in the process.
+///
+/// This is a shared clock so that timestamps taken by publishers and subscribers on different
+/// threads can be compared. It never returns zero, which marks an envelope without a timestamp.
+pub(crate) fn now() -> u64 {
+    EPOCH.get_or_init(Instant::now).elapsed().as_nanos() as u64 + 1
+}
+
+/// Converts a timestamp taken by [`now`] back into an [`Instant`].
+pub(crate) fn to_instant(timestamp: u64) -> Instant {
+    *EPOCH.get_or_init(Instant::now) + Duration::from_nanos(timestamp.saturating_sub(1))
+}
+
+const fn bucket_index(nanos: u64) -> usize {
+    if nanos < SUB_BUCKET_COUNT {
+        return nanos as usize;
+    }
+
+    let msb = 63 - nanos.leading_zeros();
+    let shift = msb + 1 - SUB_BUCKET_BITS;
+    let top = nanos >> shift;
+
+    (SUB_BUCKET_COUNT + (shift as u64 - 1) * SUB_BUCKET_HALF + (top - SUB_BUCKET_HALF)) as usize
+}
+
+/// Returns the highest latency that is recorded into the bucket at `idx`.
+fn highest_in_bucket(idx: usize) -> u64 {
+    let idx = idx as u64;
+
+    if idx < SUB_BUCKET_COUNT {
+        return idx;
+    }
+
+    let offset = idx - SUB_BUCKET_COUNT;
+    let shift = offset / SUB_BUCKET_HALF + 1;
+    let top = offset % SUB_BUCKET_HALF + SUB_BUCKET_HALF;
+
+    ((top + 1) << shift) - 1
+}
+
+/// Records the publish-to-read latencies of a single subscriber.
+///
+/// Recording takes a few relaxed atomic operations on buckets that only the owning subscriber
+/// writes to, so it stays off the publishers' path.
+pub(crate) struct LatencyRecorder {
+    counts: Box<[AtomicU64]>,
+    sum_nanos: AtomicU64,
+    min_nanos: AtomicU64,
+    max_nanos: AtomicU64,
+}
+
+impl LatencyRecorder {
+    pub fn new() -> Self {
+        Self {
+            counts: (0..BUCKET_COUNT).map(|_| AtomicU64::new(0)).collect(),
+            sum_nanos: AtomicU64::new(0),
+            min_nanos: AtomicU64::new(u64::MAX),
+            max_nanos: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, nanos: u64) {
+        let nanos = std::cmp::min(nanos, MAX_TRACKABLE_NANOS);
+
+        self.counts[bucket_index(nanos)].fetch_add(1, Ordering::Relaxed);
+        self.sum_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.min_nanos.fetch_min(nanos, Ordering::Relaxed);
+        self.max_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> LatencyHistogram {
+        LatencyHistogram {
+            counts: self
+                .counts
+                .iter()
+                .map(|count| count.load(Ordering::Relaxed))
+                .collect(),
+            sum_nanos: self.sum_nanos.load(Ordering::Relaxed),
+            min_nanos: self.min_nanos.load(Ordering::Relaxed),
+            max_nanos: self.max_nanos.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A histogram of the time events spent in the ring between being published and being read.
+///
+/// Latencies are bucketed HDR-style: each power-of-two range is split into equal sub-buckets, so
+/// reported values are within 2% of the recorded latencies, up to a maximum of about 68 seconds.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::builder().track_latency(true).build()?;
+/// let subscriber = eventbus.subscribe::<usize>();
+///
+/// eventbus.publish(1_usize);
+/// subscriber.recv()?;
+///
+/// let histogram = subscriber.latency_histogram().unwrap();
+/// println!("p99: {:?}", histogram.value_at_quantile(0.99));
+/// ```
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LatencyHistogram {
+    counts: Vec<u64>,
+    sum_nanos: u64,
+    min_nanos: u64,
+    max_nanos: u64,
+}
+
+impl LatencyHistogram {
+    /// Creates a histogram without any recorded latencies.
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; BUCKET_COUNT],
+            sum_nanos: 0,
+            min_nanos: u64::MAX,
+            max_nanos: 0,
+        }
+    }
+
+    /// Get the number of recorded latencies.
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Get the lowest recorded latency, or zero if none were recorded.
+    pub fn min(&self) -> Duration {
+        if self.is_empty() {
+            Duration::from_nanos(0)
+        } else {
+            Duration::from_nanos(self.min_nanos)
+        }
+    }
+
+    /// Get the highest recorded latency.
+    pub fn max(&self) -> Duration {
+        Duration::from_nanos(self.max_nanos)
+    }
+
+    /// Get the mean of the recorded latencies, or zero if none were recorded.
+    pub fn mean(&self) -> Duration {
+        match self.count() {
+            0 => Duration::from_nanos(0),
+            count => Duration::from_nanos(self.sum_nanos / count),
+        }
+    }
+
+    /// Get the latency that `quantile` of the recorded latencies are at or below, where
+    /// `quantile` is between 0.0 and 1.0.
+    pub fn value_at_quantile(&self, quantile: f64) -> Duration {
+        let count = self.count();
+        if count == 0 {
+            return Duration::from_nanos(0);
+        }
+
+        let quantile = quantile.clamp(0.0, 1.0);
+        let rank = std::cmp::max((quantile * count as f64).ceil() as u64, 1);
+
+        let mut seen = 0;
+        for (idx, bucket_count) in self.counts.iter().enumerate() {
+            seen += bucket_count;
+
+            if seen >= rank {
+                let highest = std::cmp::min(highest_in_bucket(idx), self.max_nanos);
+                return Duration::from_nanos(std::cmp::max(highest, self.min_nanos));
+            }
+        }
+
+        self.max()
+    }
+
+    /// Adds all latencies recorded in `other` to this histogram.
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        for (count, other_count) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other_count;
+        }
+
+        self.sum_nanos += other.sum_nanos;
+        self.min_nanos = std::cmp::min(self.min_nanos, other.min_nanos);
+        self.max_nanos = std::cmp::max(self.max_nanos, other.max_nanos);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.counts.iter().all(|count| *count == 0)
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::latency::*;
+
+    #[test]
+    fn buckets_cover_every_latency() {
+        for nanos in [
+            0,
+            1,
+            127,
+            128,
+            129,
+            255,
+            256,
+            1_000_000,
+            MAX_TRACKABLE_NANOS,
+        ] {
+            let idx = bucket_index(nanos);
+            assert!(idx < BUCKET_COUNT);
+            assert!(highest_in_bucket(idx) >= nanos);
+
+            // Within 2% of the recorded latency
+            assert!(highest_in_bucket(idx) - nanos <= nanos / 50);
+        }
+    }
+
+    #[test]
+    fn quantiles_and_merge() {
+        let recorder = LatencyRecorder::new();
+        for micros in 1..=100 {
+            recorder.record(micros * 1_000);
+        }
+
+        let mut histogram = recorder.snapshot();
+        assert_eq!(100, histogram.count());
+        assert_eq!(Duration::from_micros(1), histogram.min());
+        assert_eq!(Duration::from_micros(100), histogram.max());
+
+        let p50 = histogram.value_at_quantile(0.5).as_nanos() as u64;
+        assert!((50_000..=51_000).contains(&p50));
+
+        let other = LatencyRecorder::new();
+        other.record(200_000);
+
+        histogram.merge(&other.snapshot());
+        assert_eq!(101, histogram.count());
+        assert_eq!(Duration::from_micros(200), histogram.value_at_quantile(1.0));
+    }
+}
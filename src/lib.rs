@@ -23,10 +23,10 @@
 //! let mut publisher = eventbus.publisher();
 //! publisher.send(i + 1111);
 //!
-//! let mut msg = subscriber.recv();
+//! let mut msg = subscriber.recv().unwrap();
 //! assert_eq!(i, *msg);
 //!
-//! msg = subscriber.recv();
+//! msg = subscriber.recv().unwrap();
 //! assert_eq!(i + 1111, *msg);
 //! ```
 //!
@@ -42,7 +42,7 @@
 //! let i: usize = 1234;
 //! publisher.send(i).await?;
 //!
-//! let msg = subscriber.next().await?;
+//! let msg = subscriber.next().await.unwrap()?;
 //! assert_eq!(i, *msg);
 //! ```
 //!
@@ -56,9 +56,20 @@
 //! [WaitStrategies](https://docs.rs/eventador/latest/eventador/enum.WaitStrategy.html), with the
 //! default being to wait for all subscribers to read an event before it is overwritten.
 //!
+//! An [EvictionPolicy](https://docs.rs/eventador/latest/eventador/struct.EvictionPolicy.html) can
+//! also be set so that a stuck subscriber is evicted rather than halting all publishers.
+//!
 //! # Feature Flags
 //!
 //! - `async`: enables usage of async APIs
+//! - `bridge`: enables bridging events between event-buses over TCP or Unix domain sockets
+//! - `metrics`: enables OpenMetrics rendering and reporting to the `metrics` crate facade
+//! - `journal`: enables journaling published events to disk and recovering them after a restart
+//! - `registry`: enables encoding and decoding events by a stable type name with `serde`
+//! - `shm`: enables a ring in shared memory for plain-old-data events, that processes on the same
+//!   host attach to by name (unix only)
+//! - `tracing`: propagates `tracing` span context from publishers to subscribers, and emits
+//!   trace-level events for claims, waits, overwrites and lag skips
 //!
 //! # Design Considerations
 //!
@@ -105,34 +116,105 @@
 
 // #![feature(doc_cfg)]
 
-mod alertable;
+mod builder;
+mod conflating_subscriber;
+mod error;
 mod event;
+mod eviction;
+mod forward;
+mod handler;
+mod hooks;
+mod latency;
 mod publisher;
+mod request;
 mod ring_buffer;
+mod schedule;
 mod sequence;
+mod stats;
 mod subscriber;
+mod throttle;
 mod wait_strategy;
+mod waiter;
+mod window;
+
+#[cfg(feature = "bridge")]
+// #[doc(cfg(feature = "bridge"))]
+mod bridge;
 
 #[cfg(feature = "async")]
 // #[doc(cfg(feature = "async"))]
 mod futures;
 
+#[cfg(feature = "journal")]
+// #[doc(cfg(feature = "journal"))]
+mod journal;
+
+#[cfg(feature = "metrics")]
+// #[doc(cfg(feature = "metrics"))]
+mod metrics;
+
+#[cfg(feature = "registry")]
+// #[doc(cfg(feature = "registry"))]
+mod registry;
+
+#[cfg(all(feature = "shm", unix))]
+// #[doc(cfg(all(feature = "shm", unix)))]
+mod shm;
+
 #[cfg(feature = "async")]
 // #[doc(cfg(feature = "async"))]
-pub use crate::futures::{AsyncPublisher, AsyncSubscriber, PublishError};
+pub use crate::futures::{
+    AsyncDebouncedSubscriber, AsyncPublisher, AsyncSubscriber, AsyncThrottledSubscriber,
+    AsyncWindowedSubscriber, PublishError,
+};
+
+#[cfg(feature = "bridge")]
+// #[doc(cfg(feature = "bridge"))]
+pub use crate::bridge::{BridgeAddr, BridgePublisher, BridgeSubscriber};
+
+#[cfg(feature = "journal")]
+// #[doc(cfg(feature = "journal"))]
+pub use crate::journal::{FsyncPolicy, Journal};
+
+#[cfg(feature = "metrics")]
+// #[doc(cfg(feature = "metrics"))]
+pub use crate::metrics::render_openmetrics;
+
+#[cfg(feature = "registry")]
+// #[doc(cfg(feature = "registry"))]
+pub use crate::registry::{Codec, TypeRegistry};
+
+#[cfg(all(feature = "shm", unix))]
+// #[doc(cfg(all(feature = "shm", unix)))]
+pub use crate::shm::{SharedEventador, SharedPublisher, SharedSubscriber};
 
 #[cfg(feature = "async")]
 // #[doc(cfg(feature = "async"))]
 pub use ::futures::{SinkExt, StreamExt};
 
-pub use event::EventRead;
-pub use publisher::Publisher;
+pub use builder::{EventadorBuilder, ProducerType, StartPosition};
+pub use conflating_subscriber::ConflatingSubscriber;
+pub use error::Error;
+pub use event::{AnyEvent, EventRead, SharedEvent};
+pub use eviction::{Eviction, EvictionPolicy, EvictionReason};
+pub use forward::Forwarder;
+pub use handler::{DeadLetter, FailurePolicy, Handler, HandlerBuilder};
+pub use hooks::Hooks;
+pub use latency::LatencyHistogram;
+pub use publisher::{Publisher, TrySendError};
+pub use request::{Reply, Request, Responder};
+pub use schedule::ScheduledEvent;
+pub use stats::{PublishedCount, Stats, SubscriberStats};
 pub use subscriber::Subscriber;
-pub use wait_strategy::WaitStrategy;
+pub use throttle::{DebouncedSubscriber, ThrottledSubscriber};
+pub use wait_strategy::{Backoff, WaitStrategy};
+pub use window::{Window, WindowedSubscriber};
 
 use crate::ring_buffer::RingBuffer;
-use crate::sequence::Sequence;
+use crate::sequence::gating_sequence::GatingSequence;
+use std::hash::Hash;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// A lock-free and thread-safe event-bus implementation.
 ///
@@ -147,7 +229,7 @@ use std::sync::Arc;
 /// let mut i: usize = 1234;
 /// eventbus.publish(i);
 ///
-/// let mut msg = subscriber.recv();
+/// let mut msg = subscriber.recv()?;
 /// assert_eq!(i, *msg);
 /// ```
 ///
@@ -172,10 +254,8 @@ impl Eventador {
     /// let eventbus = Eventador::new(4)?;
     /// ```
     ///
-    pub fn new(capacity: u64) -> anyhow::Result<Self> {
-        Ok(Self {
-            ring: Arc::new(RingBuffer::new(capacity, WaitStrategy::AllSubscribers)?),
-        })
+    pub fn new(capacity: u64) -> Result<Self, Error> {
+        Self::builder().capacity(capacity).build()
     }
 
     /// Creates a new Eventador event-bus with a specific [`WaitStrategy`] for publishers.
@@ -187,13 +267,125 @@ impl Eventador {
     /// Basic usage:
     ///
     /// ```ignore
-    /// let eventbus = Eventador::new(4, WaitStrategy::AllSubscribers)?;
+    /// let eventbus = Eventador::with_strategy(4, WaitStrategy::AllSubscribers)?;
+    /// ```
+    ///
+    pub fn with_strategy(capacity: u64, wait_strategy: WaitStrategy) -> Result<Self, Error> {
+        Self::builder()
+            .capacity(capacity)
+            .wait_strategy(wait_strategy)
+            .build()
+    }
+
+    /// Creates an [`EventadorBuilder`] to configure a new Eventador event-bus.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::builder()
+    ///     .name("orders")
+    ///     .capacity(1024)
+    ///     .eviction_policy(EvictionPolicy::new().max_stall(Duration::from_secs(1)))
+    ///     .build()?;
+    /// ```
+    ///
+    pub fn builder() -> EventadorBuilder {
+        EventadorBuilder::new()
+    }
+
+    /// Get the name of the event-bus, if it was given one through the [`EventadorBuilder`].
+    pub fn name(&self) -> Option<&str> {
+        self.ring.name()
+    }
+
+    /// Takes a snapshot of the throughput, lag and ring occupancy of the event-bus.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let subscriber = eventbus.subscribe::<usize>();
+    ///
+    /// eventbus.publish(1_usize);
+    ///
+    /// let stats = eventbus.stats();
+    /// assert_eq!(1, stats.subscribers[0].lag);
+    /// ```
+    ///
+    pub fn stats(&self) -> Stats {
+        self.ring.stats()
+    }
+
+    /// Reports the current lag, occupancy, publish and drop counts of the event-bus to the
+    /// installed `metrics` recorder.
+    ///
+    /// Publish-to-receive latencies are recorded as events are received, so this only needs to be
+    /// called periodically, such as before each scrape.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::builder().name("orders").build()?;
+    /// eventbus.report_metrics();
     /// ```
     ///
-    pub fn with_strategy(capacity: u64, wait_strategy: WaitStrategy) -> anyhow::Result<Self> {
-        Ok(Self {
-            ring: Arc::new(RingBuffer::new(capacity, wait_strategy)?),
-        })
+    #[cfg(feature = "metrics")]
+    pub fn report_metrics(&self) {
+        crate::metrics::report(&self.stats());
+    }
+
+    /// Replays every journaled event in the log at `path` into the event-bus, so that subscribers
+    /// can rebuild their state after a restart. Returns the number of events that were replayed.
+    ///
+    /// Events are decoded with the types registered on the [`Journal`] the event-bus was built
+    /// with, and events of any other types are skipped. Replayed events are not journaled again.
+    ///
+    /// Replayed events gate on subscribers like any other, so a log with more events than the ring
+    /// holds can only be replayed while subscribers drain it on other threads. Each event waits for
+    /// room for at most the journal's [`replay_timeout`](Journal::replay_timeout), and the events
+    /// replayed before that stay published. Events are replayed from the calling thread, which
+    /// with [`ProducerType::Single`] must be the thread that publishes.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let journal = Journal::new("data/orders").register::<Order>("order");
+    /// let eventbus = Eventador::builder().journal(journal).build()?;
+    ///
+    /// let subscriber = eventbus.subscribe::<Order>();
+    /// let replayed = eventbus.recover("data/orders")?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if the event-bus was built without a journal, or
+    /// [`Error::Journal`] if the log could not be read, or [`Error::Codec`] if an event could not be
+    /// decoded, or [`Error::Timeout`] if there was no room in the ring to replay an event in time.
+    ///
+    #[cfg(feature = "journal")]
+    pub fn recover(&self, path: impl AsRef<std::path::Path>) -> Result<u64, Error> {
+        self.ring.recover(path.as_ref())
+    }
+
+    /// Blocks until every event published so far has been appended to the journal and synced to
+    /// disk, according to the journal's [`FsyncPolicy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if the event-bus was built without a journal, or
+    /// [`Error::Journal`] if the journal failed to write events.
+    ///
+    #[cfg(feature = "journal")]
+    pub fn sync_journal(&self) -> Result<(), Error> {
+        self.ring.sync_journal()
     }
 
     /// Synchronously publish an event to the event-bus.
@@ -211,10 +403,139 @@ impl Eventador {
     ///
     pub fn publish<T: 'static + Send + Sync>(&self, message: T) {
         let sequence = self.ring.next();
+        self.ring.write(sequence, message);
+    }
 
-        if let Some(event_store) = self.ring.get_envelope(sequence).clone() {
-            event_store.overwrite::<T>(sequence, message);
-        }
+    /// Decodes an event that was encoded under the type name `name`, and publishes it to the
+    /// event-bus.
+    ///
+    /// This lets events be published without knowing their type at compile time, such as events
+    /// received from another process.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let registry = TypeRegistry::new().register::<Order>("order");
+    /// let eventbus = Eventador::new(4)?;
+    ///
+    /// let bytes = registry.encode(&order)?;
+    /// eventbus.publish_encoded(&registry, "order", &bytes)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnregisteredType`] if `name` is not registered, or [`Error::Codec`] if
+    /// the event could not be decoded. The event is not published in either case.
+    ///
+    #[cfg(feature = "registry")]
+    pub fn publish_encoded(
+        &self,
+        registry: &TypeRegistry,
+        name: &str,
+        bytes: &[u8],
+    ) -> Result<(), Error> {
+        registry.publish(&self.ring, name, bytes, Default::default())
+    }
+
+    /// Publishes an event to the event-bus once `delay` has passed.
+    ///
+    /// Scheduled events are held in a timer wheel with a resolution of a millisecond, which a
+    /// single thread per event-bus publishes from as they come due. Events that come due at the
+    /// same time are published in the order they were scheduled. Returns a handle that can cancel
+    /// the event before it is published.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if the event-bus was built with [`ProducerType::Single`],
+    /// since events are published from the scheduler's thread, or [`Error::Spawn`] if this is the
+    /// first event scheduled on the event-bus, and the scheduler's thread could not be started.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    ///
+    /// let timeout = eventbus.publish_after(Duration::from_secs(30), OrderTimedOut(id))?;
+    /// timeout.cancel();
+    /// ```
+    ///
+    pub fn publish_after<T: 'static + Send + Sync>(
+        &self,
+        delay: Duration,
+        message: T,
+    ) -> Result<ScheduledEvent, Error> {
+        self.publish_at(Instant::now() + delay, message)
+    }
+
+    /// Publishes an event to the event-bus at `deadline`, or as soon as possible if it has
+    /// already passed. See [`Eventador::publish_after`].
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// eventbus.publish_at(market_close, MarketClosed)?;
+    /// ```
+    ///
+    pub fn publish_at<T: 'static + Send + Sync>(
+        &self,
+        deadline: Instant,
+        message: T,
+    ) -> Result<ScheduledEvent, Error> {
+        Ok(self.ring.scheduler()?.schedule(deadline, message))
+    }
+
+    /// Attempt to publish an event to the event-bus without waiting on lagging subscribers.
+    ///
+    /// Returns the sequence of the published event, or hands the event back in
+    /// [`TrySendError::Full`] if publishing it would have to wait for subscribers to catch up.
+    /// This lets callers shed load rather than block under [`WaitStrategy::AllSubscribers`].
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    ///
+    /// if let Err(TrySendError::Full(i)) = eventbus.try_publish(1234_usize) {
+    ///     println!("dropped event {}", i);
+    /// }
+    /// ```
+    ///
+    pub fn try_publish<T: 'static + Send + Sync>(
+        &self,
+        message: T,
+    ) -> Result<u64, TrySendError<T>> {
+        self.publisher().try_send(message)
+    }
+
+    /// Publish an event to the event-bus, waiting on lagging subscribers for at most `timeout`.
+    ///
+    /// Returns the sequence of the published event, or hands the event back in
+    /// [`TrySendError::Timeout`] if it could not be published in time.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// eventbus.publish_timeout(1234_usize, Duration::from_millis(10))?;
+    /// ```
+    ///
+    pub fn publish_timeout<T: 'static + Send + Sync>(
+        &self,
+        message: T,
+        timeout: Duration,
+    ) -> Result<u64, TrySendError<T>> {
+        self.publisher().send_timeout(message, timeout)
     }
 
     /// Creates a [`Publisher`] that synchronously publishes messages on the event-bus.
@@ -257,18 +578,232 @@ impl Eventador {
     /// let mut i: usize = 1234;
     /// eventbus.publish(i);
     ///
-    /// let mut msg = subscriber.recv();
+    /// let mut msg = subscriber.recv()?;
     /// assert_eq!(i, *msg);
     /// ```
     ///
     pub fn subscribe<T: 'static + Send>(&self) -> Subscriber<T> {
-        let sequence = Arc::new(Sequence::with_value(self.ring.sequencer().get() + 1));
+        self.subscribe_from(self.ring.start_position())
+    }
 
-        self.ring
-            .sequencer()
-            .register_gating_sequence(sequence.clone());
+    /// Creates a [`Subscriber`] that starts reading from a specific position in the ring, rather
+    /// than the default [`StartPosition`] of the event-bus.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// eventbus.publish(1234_usize);
+    ///
+    /// let subscriber = eventbus.subscribe_from::<usize>(StartPosition::Latest);
+    ///
+    /// let mut msg = subscriber.recv()?;
+    /// assert_eq!(1234, *msg);
+    /// ```
+    ///
+    pub fn subscribe_from<T: 'static + Send>(&self, position: StartPosition) -> Subscriber<T> {
+        Subscriber::new(self.ring.clone(), self.gating_sequence(position))
+    }
+
+    /// Creates a [`ConflatingSubscriber`] that only receives the newest unread event per key.
+    ///
+    /// Unread events of the subscribed type that map to the same key through `key_fn` are collapsed,
+    /// so a slow subscriber skips over superseded events instead of reading through its backlog.
+    /// This is useful for streams where only the latest state matters, such as price-ticks.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut subscriber = eventbus.subscribe_conflated::<(char, usize), char>(|tick| tick.0);
+    ///
+    /// eventbus.publish(('a', 1));
+    /// eventbus.publish(('a', 2));
+    ///
+    /// let msg = subscriber.recv()?;
+    /// assert_eq!(('a', 2), *msg);
+    /// ```
+    ///
+    pub fn subscribe_conflated<T: 'static + Send, K: Eq + Hash + Clone>(
+        &self,
+        key_fn: impl Fn(&T) -> K + Send + 'static,
+    ) -> ConflatingSubscriber<T, K> {
+        let sequence = self.gating_sequence(self.ring.start_position());
+        ConflatingSubscriber::new(self.ring.clone(), sequence, Box::new(key_fn))
+    }
 
-        Subscriber::new(self.ring.clone(), sequence)
+    /// Publishes a request to the event-bus, and returns a [`Reply`] that receives the response
+    /// of type `Resp` that a [`Responder`] publishes for it.
+    ///
+    /// The request and its response are correlated through the metadata they are published with,
+    /// so neither type needs to carry an id of its own. The request is published from the calling
+    /// thread and the response from the responder's, so with [`ProducerType::Single`] both must be
+    /// the thread that publishes.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let responder = eventbus.responder::<usize, String>();
+    ///
+    /// let reply = eventbus.request::<usize, String>(1234);
+    ///
+    /// let request = responder.recv()?;
+    /// request.respond(request.to_string());
+    ///
+    /// let msg = reply.recv_timeout(Duration::from_secs(1))?;
+    /// assert_eq!("1234", *msg);
+    /// ```
+    ///
+    pub fn request<Req, Resp>(&self, request: Req) -> Reply<Resp>
+    where
+        Req: 'static + Send + Sync,
+        Resp: 'static + Send,
+    {
+        Reply::publish(self.ring.clone(), request)
+    }
+
+    /// Creates a [`Responder`] that receives requests of type `Req` published with
+    /// [`Eventador::request`], and replies to them with responses of type `Resp`.
+    ///
+    /// Responses are published from the thread that responds, alongside the requests, so with
+    /// [`ProducerType::Single`] requests and responses must be published from the same thread.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let responder = eventbus.responder::<usize, String>();
+    ///
+    /// let request = responder.recv()?;
+    /// request.respond(request.to_string());
+    /// ```
+    ///
+    pub fn responder<Req, Resp>(&self) -> Responder<Req, Resp>
+    where
+        Req: 'static + Send,
+        Resp: 'static + Send + Sync,
+    {
+        let sequence = self.gating_sequence(self.ring.start_position());
+        Responder::new(self.ring.clone(), sequence)
+    }
+
+    /// Creates a [`HandlerBuilder`] that spawns a [`Handler`], which calls a handler function on
+    /// every event of type `T` on a dedicated thread.
+    ///
+    /// The handler function is managed, so that a panic in it does not stop the handler's sequence
+    /// from advancing and hold back publishers. The event it panicked on is published as a
+    /// [`DeadLetter`] instead, once the [`FailurePolicy`] gives up on it.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let dead_letters = eventbus.subscribe::<DeadLetter>();
+    ///
+    /// let handler = eventbus.handler::<usize>().spawn(|i| assert_ne!(1234, *i))?;
+    /// eventbus.publish(1234_usize);
+    ///
+    /// let failed = dead_letters.recv()?;
+    /// assert_eq!(handler.id(), failed.handler_id);
+    /// ```
+    ///
+    pub fn handler<T: 'static + Send>(&self) -> HandlerBuilder<T> {
+        HandlerBuilder::new(self.ring.clone(), self.subscribe::<T>())
+    }
+
+    /// Forwards every event of type `T` that is published to this event-bus on to `target`, on a
+    /// dedicated thread.
+    ///
+    /// The forwarder subscribes like any other subscriber, so this event-bus's publishers wait on
+    /// it according to the wait-strategy, and it in turn waits on the subscribers of `target`.
+    /// Events that were first published on `target`, or forwarded through it, are never forwarded
+    /// back to it, so event-buses can forward to each other without events circling between them.
+    /// Events that the forwarder misses because they were overwritten are recorded as lag on both
+    /// event-buses.
+    ///
+    /// Forwarding stops once every handle to this event-bus or to `target` is dropped, once the
+    /// forwarder is evicted from this event-bus, or once the returned [`Forwarder`] is stopped.
+    /// Event-buses that forward to each other with [`WaitStrategy::AllSubscribers`] can deadlock
+    /// once both are full.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if `target` was built with [`ProducerType::Single`], since
+    /// events are published from the forwarding thread, or [`Error::Spawn`] if the forwarding
+    /// thread could not be started.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let parent = Eventador::new(1024)?;
+    /// let child = Eventador::new(64)?;
+    /// let subscriber = child.subscribe::<usize>();
+    ///
+    /// parent.forward::<usize>(&child)?;
+    /// parent.publish(1234_usize);
+    ///
+    /// let mut msg = subscriber.recv()?;
+    /// assert_eq!(1234, *msg);
+    /// ```
+    ///
+    pub fn forward<T: 'static + Send + Sync + Clone>(
+        &self,
+        target: &Eventador,
+    ) -> Result<Forwarder, Error> {
+        Forwarder::spawn(self, target, Box::new(|event: &T| Some(event.clone())))
+    }
+
+    /// Forwards the events of type `T` that match `filter` on to `target`, like
+    /// [`Eventador::forward`].
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let forwarder = orders.forward_filter::<Order, _>(&large_orders, |order| order.size > 100)?;
+    /// ```
+    ///
+    pub fn forward_filter<T, F>(&self, target: &Eventador, filter: F) -> Result<Forwarder, Error>
+    where
+        T: 'static + Send + Sync + Clone,
+        F: Fn(&T) -> bool + Send + 'static,
+    {
+        let f = move |event: &T| filter(event).then(|| event.clone());
+        Forwarder::spawn(self, target, Box::new(f))
+    }
+
+    /// Forwards every event of type `A` on to `target` as the event of type `B` that `f` maps it
+    /// to, like [`Eventador::forward`].
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let forwarder = orders.forward_map::<Order, OrderId, _>(&audit, |order| order.id)?;
+    /// ```
+    ///
+    pub fn forward_map<A, B, F>(&self, target: &Eventador, f: F) -> Result<Forwarder, Error>
+    where
+        A: 'static + Send + Sync,
+        B: 'static + Send + Sync,
+        F: Fn(&A) -> B + Send + 'static,
+    {
+        Forwarder::spawn(self, target, Box::new(move |event: &A| Some(f(event))))
     }
 
     /// Creates an [`AsyncPublisher`] that can publish to the event-bus asynchronously.
@@ -276,8 +811,8 @@ impl Eventador {
     /// The buffer size indicates the number of events that can be buffered until a flush is made
     /// to the event bus. Until events are flushed to the event bus, they are not yet published.
     ///
-    /// Because events are buffered, an AsyncPublisher can only publish events of the same
-    /// type. A new AsyncPublisher must be instantiated for events of another type.
+    /// A typed AsyncPublisher only buffers events of its own type. An untyped `AsyncPublisher`,
+    /// which buffers [`AnyEvent`]s, can publish events of any type.
     ///
     /// # Example
     ///
@@ -289,6 +824,9 @@ impl Eventador {
     ///
     /// let mut i: usize = 1234;
     /// publisher.send(i).await?;
+    ///
+    /// let mut untyped: AsyncPublisher = eventbus.async_publisher(10);
+    /// untyped.publish(String::from("eventador")).await?;
     /// ```
     ///
     #[cfg(feature = "async")]
@@ -316,19 +854,22 @@ impl Eventador {
     /// let mut i: usize = 1234;
     /// publisher.send(i).await?;
     ///
-    /// let mut msg = subscriber.next().await.unwrap();
+    /// let mut msg = subscriber.next().await.unwrap()?;
     /// assert_eq!(i, *msg);
     /// ```
     ///
     #[cfg(feature = "async")]
     // #[doc(cfg(feature = "async"))]
     pub fn async_subscriber<T: Send + Unpin>(&self) -> AsyncSubscriber<T> {
-        let sequence = Arc::new(Sequence::with_value(self.ring.sequencer().get() + 1));
+        let sequence = self.gating_sequence(self.ring.start_position());
+        AsyncSubscriber::new(self.ring.clone(), sequence)
+    }
+
+    fn gating_sequence(&self, position: StartPosition) -> Arc<GatingSequence> {
+        let initial_value = self.ring.start_sequence(position);
         self.ring
             .sequencer()
-            .register_gating_sequence(sequence.clone());
-
-        AsyncSubscriber::new(self.ring.clone(), sequence)
+            .register_gating_sequence(initial_value)
     }
 }
 
@@ -363,7 +904,14 @@ mod tests {
     #[cfg(feature = "async")]
     use ntest::timeout;
 
-    use crate::Eventador;
+    use crate::{Error, Eventador};
+
+    #[test]
+    fn invalid_capacity() {
+        assert_eq!(Some(Error::InvalidCapacity(0)), Eventador::new(0).err());
+        assert_eq!(Some(Error::InvalidCapacity(1)), Eventador::new(1).err());
+        assert_eq!(Some(Error::InvalidCapacity(6)), Eventador::new(6).err());
+    }
 
     #[test]
     fn publish_and_subscribe() {
@@ -378,7 +926,7 @@ mod tests {
         let mut i: usize = 1234;
         eventbus.publish(i);
 
-        let mut msg = subscriber.recv();
+        let mut msg = subscriber.recv().unwrap();
         assert_eq!(i, *msg);
 
         i += 1111;
@@ -389,7 +937,7 @@ mod tests {
             eventbus2.publish(i);
         });
 
-        msg = subscriber.recv();
+        msg = subscriber.recv().unwrap();
         assert_eq!(i, *msg);
     }
 
@@ -420,7 +968,7 @@ mod tests {
             reg,
         ));
 
-        let mut msg = subscriber.next().await.unwrap();
+        let mut msg = subscriber.next().await.unwrap().unwrap();
         assert_eq!(i, *msg);
         println!("Passed part 1!");
 
@@ -432,7 +980,7 @@ mod tests {
             eventbus2.publish(i);
         });
 
-        msg = subscriber.next().await.unwrap();
+        msg = subscriber.next().await.unwrap().unwrap();
         assert_eq!(i, *msg);
         println!("Passed part 2!");
 
@@ -440,7 +988,7 @@ mod tests {
         sent = sender.send(Ok(i)).await;
         assert!(sent.is_ok());
 
-        msg = subscriber.next().await.unwrap();
+        msg = subscriber.next().await.unwrap().unwrap();
         assert_eq!(i, *msg);
         println!("Passed part 3! Done.");
 
@@ -466,7 +1014,7 @@ mod tests {
 
         eventbus.publish(TestEnum::SampleA);
 
-        let msg = subscriber.recv();
+        let msg = subscriber.recv().unwrap();
         assert_eq!(TestEnum::SampleA, *msg);
         println!("Passed part 3! Done.");
     }
@@ -0,0 +1,357 @@
+use crate::stats::Stats;
+use crate::Eventador;
+use crossbeam::utils::CachePadded;
+use metrics_facade::{counter, gauge, histogram, Histogram};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Upper bounds of the publish-to-receive latency histogram buckets, in nanoseconds.
+const LATENCY_BUCKETS: [u64; 13] = [
+    1_000,
+    5_000,
+    10_000,
+    50_000,
+    100_000,
+    500_000,
+    1_000_000,
+    5_000_000,
+    10_000_000,
+    50_000_000,
+    100_000_000,
+    500_000_000,
+    1_000_000_000,
+];
+
+/// The latency metrics of a single event-bus, recorded as subscribers receive events.
+///
+/// The facade histogram is registered when the event-bus is built, so a `metrics` recorder needs
+/// to be installed before then for latencies to reach it.
+pub(crate) struct BusMetrics {
+    latency: LatencyBuckets,
+    latency_handle: Histogram,
+}
+
+impl BusMetrics {
+    pub fn new(name: Option<&str>) -> Self {
+        Self {
+            latency: LatencyBuckets::new(),
+            latency_handle: histogram!(
+                "eventador_publish_to_receive_seconds",
+                "bus" => bus_label(name).to_string()
+            ),
+        }
+    }
+
+    pub fn record_latency(&self, latency: u64) {
+        self.latency.record(latency);
+        self.latency_handle.record(latency as f64 / 1e9);
+    }
+}
+
+struct LatencyBuckets {
+    buckets: Vec<CachePadded<AtomicU64>>,
+    sum_nanos: CachePadded<AtomicU64>,
+}
+
+impl LatencyBuckets {
+    fn new() -> Self {
+        Self {
+            // The last bucket counts latencies above the largest bound
+            buckets: (0..=LATENCY_BUCKETS.len())
+                .map(|_| CachePadded::new(AtomicU64::new(0)))
+                .collect(),
+            sum_nanos: CachePadded::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn record(&self, nanos: u64) {
+        let idx = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| nanos <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        self.sum_nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    /// Returns the cumulative count of each bucket, with the total count last.
+    fn cumulative_counts(&self) -> Vec<u64> {
+        self.buckets
+            .iter()
+            .scan(0, |total, bucket| {
+                *total += bucket.load(Ordering::Relaxed);
+                Some(*total)
+            })
+            .collect()
+    }
+
+    fn sum_seconds(&self) -> f64 {
+        self.sum_nanos.load(Ordering::Relaxed) as f64 / 1e9
+    }
+}
+
+/// Renders the metrics of one or more event-buses in the OpenMetrics text format.
+///
+/// Each event-bus is labelled by its name, so every bus that is rendered together should be given
+/// a distinct name through the [`EventadorBuilder`](crate::EventadorBuilder).
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let orders = Eventador::builder().name("orders").build()?;
+/// let trades = Eventador::builder().name("trades").build()?;
+///
+/// let body = eventador::render_openmetrics(&[&orders, &trades]);
+/// ```
+///
+pub fn render_openmetrics(buses: &[&Eventador]) -> String {
+    let stats: Vec<Stats> = buses.iter().map(|bus| bus.stats()).collect();
+    let mut out = String::new();
+
+    family(
+        &mut out,
+        "eventador_published",
+        "counter",
+        "Events published.",
+    );
+    for stats in &stats {
+        let mut published: Vec<_> = stats.published.values().collect();
+        published.sort_unstable_by_key(|published| published.type_name);
+
+        for published in published {
+            let _ = writeln!(
+                out,
+                "eventador_published_total{{bus=\"{}\",type=\"{}\"}} {}",
+                escape(bus_label(stats.name.as_deref())),
+                escape(published.type_name),
+                published.count
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "eventador_overwrites",
+        "counter",
+        "Unread events overwritten by publishers.",
+    );
+    for stats in &stats {
+        sample(
+            &mut out,
+            "eventador_overwrites_total",
+            stats,
+            stats.overwrites,
+        );
+    }
+
+    family(
+        &mut out,
+        "eventador_lag_drops",
+        "counter",
+        "Overwritten events skipped by lagging subscribers.",
+    );
+    for stats in &stats {
+        sample(
+            &mut out,
+            "eventador_lag_drops_total",
+            stats,
+            stats.lag_drops,
+        );
+    }
+
+    family(
+        &mut out,
+        "eventador_dead_letters",
+        "counter",
+        "Events that handlers could not handle and dead-lettered.",
+    );
+    for stats in &stats {
+        sample(
+            &mut out,
+            "eventador_dead_letters_total",
+            stats,
+            stats.dead_letters,
+        );
+    }
+
+    family(
+        &mut out,
+        "eventador_occupancy",
+        "gauge",
+        "Events in the ring not yet read by all subscribers.",
+    );
+    for stats in &stats {
+        sample(&mut out, "eventador_occupancy", stats, stats.occupancy);
+    }
+
+    family(
+        &mut out,
+        "eventador_subscribers",
+        "gauge",
+        "Subscribers gating publishers.",
+    );
+    for stats in &stats {
+        sample(
+            &mut out,
+            "eventador_subscribers",
+            stats,
+            stats.subscriber_count,
+        );
+    }
+
+    family(
+        &mut out,
+        "eventador_subscriber_lag",
+        "gauge",
+        "Published events a subscriber has not read yet.",
+    );
+    for stats in &stats {
+        for subscriber in &stats.subscribers {
+            let _ = writeln!(
+                out,
+                "eventador_subscriber_lag{{bus=\"{}\",subscriber=\"{}\"}} {}",
+                escape(bus_label(stats.name.as_deref())),
+                subscriber.id,
+                subscriber.lag
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "eventador_publish_to_receive_seconds",
+        "histogram",
+        "Time from publishing an event to a subscriber receiving it.",
+    );
+    for (bus, stats) in buses.iter().zip(&stats) {
+        let bus_name = escape(bus_label(stats.name.as_deref()));
+        let latency = &bus.ring.metrics().latency;
+        let counts = latency.cumulative_counts();
+
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(&counts) {
+            let _ = writeln!(
+                out,
+                "eventador_publish_to_receive_seconds_bucket{{bus=\"{}\",le=\"{}\"}} {}",
+                bus_name,
+                *bound as f64 / 1e9,
+                count
+            );
+        }
+
+        let total = counts.last().copied().unwrap_or(0);
+        let _ = writeln!(
+            out,
+            "eventador_publish_to_receive_seconds_bucket{{bus=\"{}\",le=\"+Inf\"}} {}",
+            bus_name, total
+        );
+        let _ = writeln!(
+            out,
+            "eventador_publish_to_receive_seconds_count{{bus=\"{}\"}} {}",
+            bus_name, total
+        );
+        let _ = writeln!(
+            out,
+            "eventador_publish_to_receive_seconds_sum{{bus=\"{}\"}} {}",
+            bus_name,
+            latency.sum_seconds()
+        );
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
+
+/// Reports the current lag, occupancy, publish and drop counts of an event-bus to the installed
+/// `metrics` recorder.
+pub(crate) fn report(stats: &Stats) {
+    let bus = bus_label(stats.name.as_deref()).to_string();
+
+    for published in stats.published.values() {
+        counter!(
+            "eventador_published_total",
+            "bus" => bus.clone(),
+            "type" => published.type_name
+        )
+        .absolute(published.count);
+    }
+
+    counter!("eventador_overwrites_total", "bus" => bus.clone()).absolute(stats.overwrites);
+    counter!("eventador_lag_drops_total", "bus" => bus.clone()).absolute(stats.lag_drops);
+    counter!("eventador_dead_letters_total", "bus" => bus.clone()).absolute(stats.dead_letters);
+
+    gauge!("eventador_occupancy", "bus" => bus.clone()).set(stats.occupancy as f64);
+    gauge!("eventador_subscribers", "bus" => bus.clone()).set(stats.subscriber_count as f64);
+
+    for subscriber in &stats.subscribers {
+        gauge!(
+            "eventador_subscriber_lag",
+            "bus" => bus.clone(),
+            "subscriber" => subscriber.id.to_string()
+        )
+        .set(subscriber.lag as f64);
+    }
+}
+
+fn bus_label(name: Option<&str>) -> &str {
+    name.unwrap_or("default")
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+}
+
+fn sample(out: &mut String, name: &str, stats: &Stats, value: impl std::fmt::Display) {
+    let _ = writeln!(
+        out,
+        "{}{{bus=\"{}\"}} {}",
+        name,
+        escape(bus_label(stats.name.as_deref())),
+        value
+    );
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{render_openmetrics, Eventador};
+
+    #[test]
+    fn renders_named_buses() {
+        let orders = Eventador::builder().name("orders").build().unwrap();
+        let trades = Eventador::builder().name("trades").build().unwrap();
+
+        let subscriber = orders.subscribe::<usize>();
+        orders.publish(1_usize);
+        orders.publish(2_usize);
+        trades.publish(1_u8);
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+
+        let body = render_openmetrics(&[&orders, &trades]);
+        assert!(body.contains("eventador_published_total{bus=\"orders\",type=\"usize\"} 2\n"));
+        assert!(body.contains("eventador_published_total{bus=\"trades\",type=\"u8\"} 1\n"));
+        assert!(body.contains("eventador_occupancy{bus=\"orders\"} 1\n"));
+        assert!(body.contains("eventador_dead_letters_total{bus=\"trades\"} 0\n"));
+        assert!(body.contains(&format!(
+            "eventador_subscriber_lag{{bus=\"orders\",subscriber=\"{}\"}} 1\n",
+            subscriber.id()
+        )));
+        assert!(body.contains(
+            "eventador_publish_to_receive_seconds_bucket{bus=\"orders\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(body.contains("eventador_publish_to_receive_seconds_count{bus=\"trades\"} 0\n"));
+        assert!(body.ends_with("# EOF\n"));
+
+        // Each metric family is declared once, across all buses
+        assert_eq!(1, body.matches("# TYPE eventador_occupancy gauge").count());
+    }
+}
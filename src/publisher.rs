@@ -1,9 +1,45 @@
 use crate::ring_buffer::RingBuffer;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[allow(unused_imports)]
 use crate::Eventador;
 
+/// An error returned when an event could not be published without waiting on lagging subscribers.
+///
+/// The unpublished event is handed back so that it can be retried, or shed by the caller.
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TrySendError<T> {
+    /// The ring is full of events that subscribers have not read yet.
+    Full(T),
+
+    /// The ring stayed full for the entire duration of the timeout.
+    Timeout(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Recovers the event that could not be published.
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Full(event) | TrySendError::Timeout(event) => event,
+        }
+    }
+}
+
+impl<T> std::fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TrySendError::Full(_) => write!(f, "ring is full of unread events"),
+            TrySendError::Timeout(_) => {
+                write!(f, "timed out waiting for an overwriteable envelope")
+            }
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::error::Error for TrySendError<T> {}
+
 /// A handle to publish events to the event-bus.
 ///
 /// Although the [`Eventador::publish`] function has the exact same behavior, this handle offers an API
@@ -45,12 +81,131 @@ impl Publisher {
     ///
     pub fn send<T: 'static + Send + Sync>(&mut self, event: T) {
         let sequence = self.ring.next();
+        self.write(sequence, event);
+    }
+
+    /// Attempt to publish an event on the event-bus without waiting on lagging subscribers.
+    ///
+    /// Returns the sequence of the published event, or hands the event back in
+    /// [`TrySendError::Full`] if publishing it would have to wait for subscribers to catch up.
+    /// Publishing never fails under [`WaitStrategy::NoWait`](crate::WaitStrategy::NoWait).
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut publisher = eventbus.publisher();
+    ///
+    /// match publisher.try_send(1234_usize) {
+    ///     Ok(sequence) => println!("published event {}", sequence),
+    ///     Err(TrySendError::Full(event)) => println!("shedding event {}", event),
+    ///     Err(err) => return Err(err.into()),
+    /// }
+    /// ```
+    ///
+    pub fn try_send<T: 'static + Send + Sync>(&mut self, event: T) -> Result<u64, TrySendError<T>> {
+        match self.ring.try_next() {
+            Ok(sequence) => {
+                self.write(sequence, event);
+                Ok(sequence)
+            }
+
+            Err(_) => Err(TrySendError::Full(event)),
+        }
+    }
+
+    /// Publish an event on the event-bus, waiting on lagging subscribers for at most `timeout`.
+    ///
+    /// Returns the sequence of the published event, or hands the event back in
+    /// [`TrySendError::Timeout`] if it could not be published in time.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut publisher = eventbus.publisher();
+    ///
+    /// publisher.send_timeout(1234_usize, Duration::from_millis(10))?;
+    /// ```
+    ///
+    pub fn send_timeout<T: 'static + Send + Sync>(
+        &mut self,
+        event: T,
+        timeout: Duration,
+    ) -> Result<u64, TrySendError<T>> {
+        match self.ring.next_timeout(timeout) {
+            Ok(sequence) => {
+                self.write(sequence, event);
+                Ok(sequence)
+            }
+
+            Err(_) => Err(TrySendError::Timeout(event)),
+        }
+    }
+
+    fn write<T: 'static + Send + Sync>(&self, sequence: u64, event: T) {
+        self.ring.write(sequence, event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Eventador, TrySendError, WaitStrategy};
+    use std::time::Duration;
+
+    #[test]
+    fn try_send_returns_event_when_full() {
+        let eventbus = Eventador::new(2).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+        let mut publisher = eventbus.publisher();
+
+        assert_eq!(Ok(1), publisher.try_send(1_usize));
+        assert_eq!(Ok(2), publisher.try_send(2_usize));
+        assert_eq!(Err(TrySendError::Full(3)), publisher.try_send(3_usize));
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert_eq!(Ok(3), publisher.try_send(3_usize));
+    }
+
+    #[test]
+    fn try_send_never_full_without_waiting() {
+        let eventbus = Eventador::with_strategy(2, WaitStrategy::NoWait).unwrap();
+        let _subscriber = eventbus.subscribe::<usize>();
+        let mut publisher = eventbus.publisher();
+
+        for i in 1..=4_usize {
+            assert_eq!(Ok(i as u64), publisher.try_send(i));
+        }
+    }
+
+    #[test]
+    fn send_timeout_waits_for_subscribers() {
+        let eventbus = Eventador::new(2).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+        let mut publisher = eventbus.publisher();
+
+        publisher.send(1_usize);
+        publisher.send(2_usize);
+
+        let timeout = Duration::from_millis(50);
+        assert_eq!(
+            Err(TrySendError::Timeout(3)),
+            publisher.send_timeout(3_usize, timeout)
+        );
 
-        let envelope = self
-            .ring
-            .get_envelope(sequence)
-            .expect("ring buffer was not pre-populated with empty event envelopes");
+        let subscriber_thread = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            *subscriber.recv().unwrap()
+        });
 
-        envelope.overwrite(sequence, event);
+        assert_eq!(
+            Ok(3),
+            publisher.send_timeout(3_usize, Duration::from_secs(5))
+        );
+        assert_eq!(1, subscriber_thread.join().unwrap());
     }
 }
@@ -0,0 +1,326 @@
+use crate::error::Error;
+use crate::event::{Event, Metadata};
+use crate::ring_buffer::RingBuffer;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The format that a [`TypeRegistry`] encodes events with.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Codec {
+    /// *Default*: Human-readable JSON, through `serde_json`.
+    #[default]
+    Json,
+
+    /// Compact binary, through `bincode`.
+    Bincode,
+}
+
+impl Codec {
+    fn encode<T: Serialize>(self, event: &T) -> Result<Vec<u8>, Error> {
+        match self {
+            Codec::Json => serde_json::to_vec(event).map_err(|err| Error::Codec(err.to_string())),
+            Codec::Bincode => {
+                bincode::serialize(event).map_err(|err| Error::Codec(err.to_string()))
+            }
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, Error> {
+        match self {
+            Codec::Json => {
+                serde_json::from_slice(bytes).map_err(|err| Error::Codec(err.to_string()))
+            }
+            Codec::Bincode => {
+                bincode::deserialize(bytes).map_err(|err| Error::Codec(err.to_string()))
+            }
+        }
+    }
+}
+
+type Encoder = fn(Codec, &dyn Any) -> Result<Vec<u8>, Error>;
+type Publisher = fn(Codec, &RingBuffer, &[u8], Metadata, Option<Duration>) -> Result<(), Error>;
+
+struct RegisteredType {
+    name: String,
+    type_id: TypeId,
+    encode: Encoder,
+    publish: Publisher,
+}
+
+/// Maps event types to stable names, so that type-erased events can be encoded and decoded with
+/// `serde`.
+///
+/// A [`TypeId`] is not stable across builds, so events are identified by the name they were
+/// registered under instead. The registry is what the [`Journal`](crate::Journal) stores events
+/// with, and it can be used to move events between processes through
+/// [`Eventador::publish_encoded`](crate::Eventador::publish_encoded).
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let registry = TypeRegistry::new()
+///     .codec(Codec::Bincode)
+///     .register::<Order>("order");
+///
+/// let bytes = registry.encode(&order)?;
+/// eventbus.publish_encoded(&registry, "order", &bytes)?;
+/// ```
+///
+#[derive(Clone, Default)]
+pub struct TypeRegistry {
+    codec: Codec,
+    by_type: HashMap<TypeId, Arc<RegisteredType>>,
+    by_name: HashMap<String, Arc<RegisteredType>>,
+}
+
+impl TypeRegistry {
+    /// Creates a registry without any types, that encodes events as JSON.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the format that events are encoded with.
+    pub fn codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Registers events of type `T` under a stable `name`, which identifies the type across
+    /// processes, restarts and builds.
+    ///
+    /// Registering a type again replaces its previous name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is already registered to a different type.
+    ///
+    pub fn register<T>(mut self, name: impl Into<String>) -> Self
+    where
+        T: 'static + Send + Sync + Serialize + DeserializeOwned,
+    {
+        let name = name.into();
+        let type_id = TypeId::of::<T>();
+
+        if let Some(registered) = self.by_name.get(&name) {
+            assert!(
+                registered.type_id == type_id,
+                "type name {:?} is already registered to a different type",
+                name
+            );
+        }
+
+        let registered = Arc::new(RegisteredType {
+            name: name.clone(),
+            type_id,
+            encode: encode::<T>,
+            publish: publish::<T>,
+        });
+
+        if let Some(previous) = self.by_type.insert(type_id, registered.clone()) {
+            self.by_name.remove(&previous.name);
+        }
+        self.by_name.insert(name, registered);
+
+        self
+    }
+
+    /// Get the name that events of type `T` are registered under.
+    pub fn name_of<T: 'static>(&self) -> Option<&str> {
+        self.by_type
+            .get(&TypeId::of::<T>())
+            .map(|registered| registered.name.as_str())
+    }
+
+    /// Encodes an event with the registry's codec.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnregisteredType`] if `T` was not registered, or [`Error::Codec`] if the
+    /// event could not be serialized.
+    ///
+    pub fn encode<T: 'static>(&self, event: &T) -> Result<Vec<u8>, Error> {
+        let registered = self
+            .by_type
+            .get(&TypeId::of::<T>())
+            .ok_or_else(|| Error::UnregisteredType(std::any::type_name::<T>().to_string()))?;
+
+        (registered.encode)(self.codec, event)
+    }
+
+    /// Decodes an event that was encoded under `name` with the registry's codec.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnregisteredType`] if `name` is not registered to `T`, or
+    /// [`Error::Codec`] if the event could not be deserialized.
+    ///
+    pub fn decode<T>(&self, name: &str, bytes: &[u8]) -> Result<T, Error>
+    where
+        T: 'static + DeserializeOwned,
+    {
+        match self.by_name.get(name) {
+            Some(registered) if registered.type_id == TypeId::of::<T>() => self.codec.decode(bytes),
+            _ => Err(Error::UnregisteredType(name.to_string())),
+        }
+    }
+
+    /// Encodes a type-erased event, or returns `None` if its type was not registered.
+    #[cfg_attr(not(any(feature = "bridge", feature = "journal")), allow(dead_code))]
+    pub(crate) fn encode_event(&self, event: &Event) -> Option<Result<(&str, Vec<u8>), Error>> {
+        let registered = self.by_type.get(&event.type_id)?;
+
+        Some(
+            (registered.encode)(self.codec, event.data.as_ref())
+                .map(|bytes| (registered.name.as_str(), bytes)),
+        )
+    }
+
+    /// Decodes an event that was encoded under `name`, and publishes it into the ring.
+    pub(crate) fn publish(
+        &self,
+        ring: &RingBuffer,
+        name: &str,
+        bytes: &[u8],
+        metadata: Metadata,
+    ) -> Result<(), Error> {
+        self.publish_with(ring, name, bytes, metadata, None)
+    }
+
+    /// Decodes an event that was encoded under `name`, and publishes it into the ring, waiting on
+    /// lagging subscribers for at most `timeout`.
+    ///
+    /// Returns [`Error::Timeout`] if the event could not be published in time.
+    #[cfg_attr(not(any(feature = "bridge", feature = "journal")), allow(dead_code))]
+    pub(crate) fn publish_timeout(
+        &self,
+        ring: &RingBuffer,
+        name: &str,
+        bytes: &[u8],
+        metadata: Metadata,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        self.publish_with(ring, name, bytes, metadata, Some(timeout))
+    }
+
+    fn publish_with(
+        &self,
+        ring: &RingBuffer,
+        name: &str,
+        bytes: &[u8],
+        metadata: Metadata,
+        timeout: Option<Duration>,
+    ) -> Result<(), Error> {
+        let registered = self
+            .by_name
+            .get(name)
+            .ok_or_else(|| Error::UnregisteredType(name.to_string()))?;
+
+        (registered.publish)(self.codec, ring, bytes, metadata, timeout)
+    }
+}
+
+fn encode<T: 'static + Serialize>(codec: Codec, event: &dyn Any) -> Result<Vec<u8>, Error> {
+    let event = event
+        .downcast_ref::<T>()
+        .expect("type was registered under a different TypeId");
+
+    codec.encode(event)
+}
+
+fn publish<T>(
+    codec: Codec,
+    ring: &RingBuffer,
+    bytes: &[u8],
+    metadata: Metadata,
+    timeout: Option<Duration>,
+) -> Result<(), Error>
+where
+    T: 'static + Send + Sync + DeserializeOwned,
+{
+    // Decode before claiming a sequence, so that a malformed event doesn't leave a gap in the ring
+    let event: T = codec.decode(bytes)?;
+
+    let sequence = match timeout {
+        Some(timeout) => ring.next_timeout(timeout)?,
+        None => ring.next(),
+    };
+    ring.write_with(sequence, event, metadata);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Codec, Error, Eventador, TypeRegistry};
+
+    #[test]
+    fn round_trips_by_name() {
+        for codec in [Codec::Json, Codec::Bincode] {
+            let registry = TypeRegistry::new()
+                .codec(codec)
+                .register::<(String, u64)>("order")
+                .register::<u64>("count");
+
+            assert_eq!(Some("order"), registry.name_of::<(String, u64)>());
+            assert_eq!(None, registry.name_of::<usize>());
+
+            let bytes = registry.encode(&(String::from("order"), 7_u64)).unwrap();
+            assert_eq!(
+                (String::from("order"), 7),
+                registry.decode::<(String, u64)>("order", &bytes).unwrap()
+            );
+
+            // A name only decodes to the type it was registered to
+            assert_eq!(
+                Some(Error::UnregisteredType(String::from("count"))),
+                registry.decode::<(String, u64)>("count", &bytes).err()
+            );
+            assert!(matches!(
+                registry.encode(&1_usize),
+                Err(Error::UnregisteredType(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn publishes_encoded_events() {
+        let registry = TypeRegistry::new()
+            .codec(Codec::Bincode)
+            .register::<u64>("count");
+
+        let eventbus = Eventador::new(4).unwrap();
+        let subscriber = eventbus.subscribe::<u64>();
+
+        let bytes = registry.encode(&42_u64).unwrap();
+        eventbus
+            .publish_encoded(&registry, "count", &bytes)
+            .unwrap();
+        assert_eq!(42, *subscriber.recv().unwrap());
+
+        assert!(matches!(
+            eventbus.publish_encoded(&registry, "count", &[1]),
+            Err(Error::Codec(_))
+        ));
+        assert!(matches!(
+            eventbus.publish_encoded(&registry, "order", &bytes),
+            Err(Error::UnregisteredType(_))
+        ));
+
+        // Rejected events are not published
+        assert_eq!(1, eventbus.stats().published_count::<u64>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_conflicting_names() {
+        let _ = TypeRegistry::new()
+            .register::<u64>("count")
+            .register::<u32>("count");
+    }
+}
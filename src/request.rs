@@ -0,0 +1,442 @@
+use crate::error::Error;
+use crate::event::{EventEnvelope, EventRead, Metadata};
+use crate::ring_buffer::RingBuffer;
+use crate::sequence::gating_sequence::GatingSequence;
+use crate::waiter::Alerter;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "async")]
+use futures::task::{Context, Poll};
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A pending reply to a request that was published with [`Eventador::request`].
+///
+/// The reply subscribes to responses of type `T` from the moment the request is published, and
+/// receives the first one that was published in response to its request. Other responses of the
+/// same type, including replies to other requests, are skipped.
+///
+/// The reply can be waited on synchronously with [`Reply::recv`] or [`Reply::recv_timeout`], or
+/// awaited as a [`Future`] with the `async` feature. Like a [`Subscriber`](crate::Subscriber), a
+/// pending reply gates publishers until it has been received, so it should be cancelled or dropped
+/// once the response is no longer of interest.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(4)?;
+///
+/// let reply = eventbus.request::<GetPrice, Price>(GetPrice("eventador"));
+/// let price = reply.recv_timeout(Duration::from_secs(1))?;
+/// ```
+///
+/// [`Eventador::request`]: crate::Eventador::request
+///
+pub struct Reply<T> {
+    ring: Arc<RingBuffer>,
+    sequence: Arc<GatingSequence>,
+    correlation_id: u64,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: 'static + Send> Reply<T> {
+    pub(crate) fn publish<Req: 'static + Send + Sync>(ring: Arc<RingBuffer>, request: Req) -> Self {
+        let correlation_id = NEXT_CORRELATION_ID.fetch_add(1, Ordering::Relaxed);
+
+        // Subscribe before publishing the request, so that a quick response can't be missed
+        let next = ring.sequencer().get() + 1;
+        let sequence = ring.sequencer().register_gating_sequence(next);
+
+        let metadata = Metadata {
+            request_id: correlation_id,
+            ..Metadata::default()
+        };
+
+        let claimed = ring.next();
+        ring.write_with(claimed, request, metadata);
+
+        Self {
+            ring,
+            sequence,
+            correlation_id,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Get the id that correlates the request with its reply.
+    pub fn correlation_id(&self) -> u64 {
+        self.correlation_id
+    }
+
+    /// Synchronously wait for the reply to the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the reply was evicted from the event-bus for lagging.
+    ///
+    pub fn recv(self) -> Result<EventRead<T>, Error> {
+        self.recv_until(None)
+    }
+
+    /// Synchronously wait for the reply to the request, for at most `timeout`.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let reply = eventbus.request::<GetPrice, Price>(GetPrice("eventador"));
+    ///
+    /// match reply.recv_timeout(Duration::from_millis(100)) {
+    ///     Ok(price) => println!("{}", *price),
+    ///     Err(Error::ReplyTimeout) => println!("no price"),
+    ///     Err(err) => return Err(err),
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ReplyTimeout`] if no reply arrived in time, or [`Error::Evicted`] if the
+    /// reply was evicted from the event-bus for lagging.
+    ///
+    pub fn recv_timeout(self, timeout: Duration) -> Result<EventRead<T>, Error> {
+        self.recv_until(Some(Instant::now() + timeout))
+    }
+
+    /// Stops waiting for the reply, which lets publishers move past any responses that were not
+    /// received yet. This is the same as dropping the reply.
+    pub fn cancel(self) {}
+
+    fn recv_until(self, deadline: Option<Instant>) -> Result<EventRead<T>, Error> {
+        let correlation_id = self.correlation_id;
+
+        recv_matching(&self.ring, &self.sequence, deadline, |metadata| {
+            metadata.reply_to == correlation_id
+        })
+        .map(|(event, _)| event)
+    }
+}
+
+impl<T> Drop for Reply<T> {
+    fn drop(&mut self) {
+        self.sequence.waiter().cancel();
+        self.ring
+            .sequencer()
+            .deregister_gating_sequence(&self.sequence);
+    }
+}
+
+#[cfg(feature = "async")]
+// #[doc(cfg(feature = "async"))]
+impl<T: 'static + Send + Unpin> Future for Reply<T> {
+    type Output = Result<EventRead<T>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let correlation_id = self.correlation_id;
+
+        loop {
+            if self.sequence.is_evicted() {
+                self.sequence.waiter().cancel();
+                return Poll::Ready(Err(Error::Evicted));
+            }
+
+            let next = self.sequence.get();
+            let envelope = self
+                .ring
+                .get_envelope(next)
+                .expect("ring buffer was not pre-populated with empty event envelopes")
+                .clone();
+
+            let envelope_sequence = envelope.sequence();
+            if next == envelope_sequence {
+                let read = read_matching(&self.ring, &self.sequence, &envelope, &|metadata| {
+                    metadata.reply_to == correlation_id
+                });
+
+                if let Some((event, _)) = read {
+                    self.sequence.waiter().cancel();
+                    return Poll::Ready(Ok(event));
+                }
+            } else if next > envelope_sequence {
+                // The waker stays registered until the next poll, and the envelope is checked again
+                // after registering it, so that the publisher can't miss it
+                let alerter = Alerter::Waker(cx.waker().clone());
+                self.sequence.waiter().wait_for(next, alerter);
+
+                if envelope.sequence() < next {
+                    return Poll::Pending;
+                }
+            } else {
+                self.sequence.set(envelope_sequence);
+
+                self.ring
+                    .record_overwritten(self.sequence.id(), next, envelope_sequence);
+            }
+        }
+    }
+}
+
+/// A handle to receive requests that were published with [`Eventador::request`], and reply to
+/// them.
+///
+/// Only events of type `Req` that were published as requests are received, and events published
+/// with [`Eventador::publish`] are skipped.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(4)?;
+/// let responder = eventbus.responder::<GetPrice, Price>();
+///
+/// std::thread::spawn(move || {
+///     while let Ok(request) = responder.recv() {
+///         let price = prices.get(request.0);
+///         request.respond(price);
+///     }
+/// });
+/// ```
+///
+/// [`Eventador::request`]: crate::Eventador::request
+/// [`Eventador::publish`]: crate::Eventador::publish
+///
+pub struct Responder<Req, Resp> {
+    ring: Arc<RingBuffer>,
+    sequence: Arc<GatingSequence>,
+    _marker: std::marker::PhantomData<(Req, Resp)>,
+}
+
+impl<Req: 'static + Send, Resp: 'static + Send + Sync> Responder<Req, Resp> {
+    pub(crate) fn new(ring: Arc<RingBuffer>, sequence: Arc<GatingSequence>) -> Self {
+        Self {
+            ring,
+            sequence,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Get the id that identifies the [`Responder`] on the event-bus, such as in an
+    /// [`Eviction`](crate::Eviction).
+    pub fn id(&self) -> u64 {
+        self.sequence.id()
+    }
+
+    /// Synchronously read the next request from the event-bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the Responder was evicted from the event-bus for lagging.
+    ///
+    pub fn recv(&self) -> Result<Request<Req, Resp>, Error> {
+        let (event, correlation_id) =
+            recv_matching(&self.ring, &self.sequence, None, |metadata| {
+                metadata.request_id != 0
+            })?;
+
+        Ok(Request {
+            event,
+            correlation_id,
+            ring: self.ring.clone(),
+            _marker: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<Req, Resp> Drop for Responder<Req, Resp> {
+    fn drop(&mut self) {
+        self.ring
+            .sequencer()
+            .deregister_gating_sequence(&self.sequence);
+    }
+}
+
+/// A request that was received by a [`Responder`].
+///
+/// Implements the [`Deref`] trait to read the request, like an [`EventRead`].
+pub struct Request<Req, Resp> {
+    event: EventRead<Req>,
+    correlation_id: u64,
+    ring: Arc<RingBuffer>,
+    _marker: std::marker::PhantomData<Resp>,
+}
+
+impl<Req, Resp: 'static + Send + Sync> Request<Req, Resp> {
+    /// Get the id that correlates the request with its reply.
+    pub fn correlation_id(&self) -> u64 {
+        self.correlation_id
+    }
+
+    /// Publishes `response` as the reply to the request.
+    pub fn respond(self, response: Resp) {
+        let metadata = Metadata {
+            reply_to: self.correlation_id,
+            ..Metadata::default()
+        };
+
+        let sequence = self.ring.next();
+        self.ring.write_with(sequence, response, metadata);
+    }
+}
+
+impl<Req, Resp> Deref for Request<Req, Resp> {
+    type Target = Req;
+
+    fn deref(&self) -> &Self::Target {
+        &self.event
+    }
+}
+
+/// Reads the event at the subscriber's sequence if it is of type `T` and its metadata `matches`,
+/// and moves the subscriber past it either way. Returns the event along with the correlation id of
+/// its request.
+fn read_matching<T: 'static>(
+    ring: &RingBuffer,
+    sequence: &GatingSequence,
+    envelope: &EventEnvelope,
+    matches: &impl Fn(&Metadata) -> bool,
+) -> Option<(EventRead<T>, u64)> {
+    let metadata = envelope
+        .with_event(|event| event.metadata.clone())
+        .unwrap_or_default();
+    let correlation_id = std::cmp::max(metadata.request_id, metadata.reply_to);
+
+    let event_opt: Option<EventRead<T>> = if matches(&metadata) {
+        envelope.read()
+    } else {
+        None
+    };
+
+    if event_opt.is_some() {
+        ring.record_receive(sequence, sequence.get(), envelope);
+    }
+
+    sequence.increment();
+    event_opt.map(|event| (event, correlation_id))
+}
+
+/// Waits for the next event of type `T` whose metadata `matches`, until the deadline if there is
+/// one.
+fn recv_matching<T: 'static>(
+    ring: &RingBuffer,
+    sequence: &GatingSequence,
+    deadline: Option<Instant>,
+    matches: impl Fn(&Metadata) -> bool,
+) -> Result<(EventRead<T>, u64), Error> {
+    loop {
+        if sequence.is_evicted() {
+            return Err(Error::Evicted);
+        }
+
+        let next = sequence.get();
+        let envelope = ring
+            .get_envelope(next)
+            .expect("ring buffer was not pre-populated with empty event envelopes");
+
+        let envelope_sequence = envelope.sequence();
+        if next == envelope_sequence {
+            if let Some(read) = read_matching(ring, sequence, &envelope, &matches) {
+                return Ok(read);
+            }
+        } else if next > envelope_sequence {
+            let timeout = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(timeout) if !timeout.is_zero() => Some(timeout),
+                    _ => return Err(Error::ReplyTimeout),
+                },
+                None => None,
+            };
+
+            let alerter = Alerter::Thread(std::thread::current());
+            sequence
+                .waiter()
+                .wait(&envelope, next, alerter, || match timeout {
+                    Some(timeout) => std::thread::park_timeout(timeout),
+                    None => std::thread::park(),
+                });
+        } else {
+            // Publisher has overwritten an event that has not been read yet, which can happen
+            // with lossy wait-strategies
+            sequence.set(envelope_sequence);
+            ring.record_overwritten(sequence.id(), next, envelope_sequence);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Eventador};
+    use std::time::Duration;
+
+    #[test]
+    fn replies_to_requests() {
+        let eventbus = Eventador::new(16).unwrap();
+        let responder = eventbus.responder::<u64, String>();
+
+        let respond_thread = std::thread::spawn(move || {
+            for _ in 0..2 {
+                let request = responder.recv().unwrap();
+                let response = format!("reply to {}", *request);
+                request.respond(response);
+            }
+        });
+
+        // Events published without a request are not received by the responder, and responses
+        // published without one are not taken as replies
+        eventbus.publish(0_u64);
+        let first = eventbus.request::<u64, String>(1);
+        eventbus.publish(String::from("not a reply"));
+        let second = eventbus.request::<u64, String>(2);
+
+        assert_ne!(first.correlation_id(), second.correlation_id());
+        assert_eq!("reply to 2", *second.recv().unwrap());
+        assert_eq!("reply to 1", *first.recv().unwrap());
+
+        respond_thread.join().unwrap();
+    }
+
+    #[test]
+    fn times_out_and_cancels() {
+        let eventbus = Eventador::new(4).unwrap();
+
+        let reply = eventbus.request::<u64, String>(1);
+        assert_eq!(
+            Some(Error::ReplyTimeout),
+            reply.recv_timeout(Duration::from_millis(10)).err()
+        );
+
+        // A cancelled reply no longer gates publishers
+        let reply = eventbus.request::<u64, String>(2);
+        reply.cancel();
+
+        for i in 0..8 {
+            eventbus.publish(i as usize);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[async_std::test]
+    async fn awaits_replies() {
+        let eventbus = Eventador::new(16).unwrap();
+        let responder = eventbus.responder::<u64, u64>();
+
+        let reply = eventbus.request::<u64, u64>(21);
+
+        std::thread::spawn(move || {
+            let request = responder.recv().unwrap();
+            let response = *request * 2;
+            request.respond(response);
+        });
+
+        assert_eq!(42, *reply.await.unwrap());
+    }
+}
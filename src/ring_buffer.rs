@@ -1,56 +1,411 @@
-use crate::event::EventEnvelope;
+use crate::builder::{EventadorBuilder, ProducerType, StartPosition};
+use crate::error::Error;
+use crate::event::{AnyEvent, EventEnvelope, Metadata};
+use crate::hooks::Hooks;
+#[cfg(feature = "journal")]
+use crate::journal::{self, Journal, JournalState};
+use crate::latency::{self, LatencyHistogram};
+#[cfg(feature = "metrics")]
+use crate::metrics::BusMetrics;
+use crate::schedule::Scheduler;
+use crate::sequence::gating_sequence::GatingSequence;
 use crate::sequence::sequencer::Sequencer;
+use crate::sequence::Sequence;
+use crate::stats::{Stats, SubscriberStats};
 use crate::WaitStrategy;
 use crossbeam::utils::CachePadded;
-use std::sync::Arc;
+#[cfg(feature = "journal")]
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 pub(crate) type EventWrapper = CachePadded<Arc<EventEnvelope>>;
 
+static NEXT_RING_ID: AtomicU64 = AtomicU64::new(1);
+
 pub struct RingBuffer {
+    id: u64,
+    name: Option<String>,
     capacity: u64,
     buffer: Vec<EventWrapper>,
     sequencer: Sequencer,
-    wait_strategy: WaitStrategy,
+    start_position: StartPosition,
+    hooks: Option<Arc<dyn Hooks>>,
+    track_latency: bool,
+
+    /// Whether publishers timestamp events even though latency isn't tracked, because a
+    /// subscriber groups them into windows by the time they were published.
+    timestamp_events: AtomicBool,
+    #[cfg(feature = "metrics")]
+    metrics: BusMetrics,
+    #[cfg(feature = "journal")]
+    journal: Option<Journal>,
+    #[cfg(feature = "journal")]
+    journal_state: OnceLock<Arc<JournalState>>,
+    scheduler: OnceLock<Scheduler>,
 }
 
 impl RingBuffer {
-    pub fn new(capacity: u64, wait_strategy: WaitStrategy) -> anyhow::Result<Self> {
-        if capacity > 1 && capacity.is_power_of_two() {
-            let sequencer = Sequencer::new(capacity, wait_strategy);
+    pub fn new(capacity: u64, wait_strategy: WaitStrategy) -> Result<Self, Error> {
+        EventadorBuilder::new()
+            .capacity(capacity)
+            .wait_strategy(wait_strategy)
+            .build_ring()
+    }
 
-            let ucapacity = capacity as usize;
-            let mut buffer = Vec::with_capacity(ucapacity);
+    /// Creates the ring from an [`EventadorBuilder`] whose options have already been validated.
+    pub(crate) fn from_builder(builder: EventadorBuilder) -> Self {
+        let sequencer = Sequencer::new(&builder);
 
-            for i in 0..ucapacity {
-                buffer.insert(i, CachePadded::new(Arc::new(EventEnvelope::new())))
-            }
+        let ucapacity = builder.capacity as usize;
+        let mut buffer = Vec::with_capacity(ucapacity);
 
-            Ok(Self {
-                capacity,
-                buffer,
-                sequencer,
-                wait_strategy,
-            })
-        } else {
-            Err(anyhow::Error::msg("expected capacity as a power of two"))
+        for i in 0..ucapacity {
+            buffer.insert(i, CachePadded::new(Arc::new(EventEnvelope::new())))
+        }
+
+        Self {
+            #[cfg(feature = "metrics")]
+            metrics: BusMetrics::new(builder.name.as_deref()),
+            id: NEXT_RING_ID.fetch_add(1, Ordering::Relaxed),
+            name: builder.name,
+            capacity: builder.capacity,
+            buffer,
+            sequencer,
+            start_position: builder.start_position,
+            hooks: builder.hooks,
+            track_latency: builder.track_latency,
+            timestamp_events: AtomicBool::new(false),
+            #[cfg(feature = "journal")]
+            journal: builder.journal,
+            #[cfg(feature = "journal")]
+            journal_state: OnceLock::new(),
+            scheduler: OnceLock::new(),
         }
     }
 
-    pub(crate) fn sequencer(&self) -> &Sequencer {
-        &self.sequencer
+    /// Get the id that identifies the ring within the process, such as in [`Metadata::origin`].
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub(crate) fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub(crate) fn hooks(&self) -> Option<&dyn Hooks> {
+        self.hooks.as_deref()
+    }
+
+    #[cfg(feature = "metrics")]
+    pub(crate) fn metrics(&self) -> &BusMetrics {
+        &self.metrics
+    }
+
+    /// Returns [`Error::InvalidConfig`] with `message` if the ring only allows a single producer,
+    /// which a thread of the event-bus itself can't publish alongside.
+    pub(crate) fn require_multi_producer(&self, message: &'static str) -> Result<(), Error> {
+        match self.sequencer.producer_type() {
+            ProducerType::Multi => Ok(()),
+            ProducerType::Single => Err(Error::InvalidConfig(message)),
+        }
     }
 
-    pub(crate) fn wait_strategy(&self) -> WaitStrategy {
-        self.wait_strategy
+    /// Get the scheduler of delayed events, which is started the first time an event is scheduled.
+    pub(crate) fn scheduler(self: &Arc<Self>) -> Result<&Scheduler, Error> {
+        self.require_multi_producer("events can't be scheduled on a single-producer event-bus")?;
+
+        if let Some(scheduler) = self.scheduler.get() {
+            return Ok(scheduler);
+        }
+
+        // A scheduler started concurrently by another thread is dropped, which stops its thread
+        let scheduler = Scheduler::spawn(Arc::downgrade(self))?;
+        Ok(self.scheduler.get_or_init(|| scheduler))
+    }
+
+    /// Starts the writer of the journal the ring was built with, if any.
+    #[cfg(feature = "journal")]
+    pub(crate) fn start_journal(self: &Arc<Self>) -> Result<(), Error> {
+        if let Some(journal) = &self.journal {
+            let state = journal::start(journal.clone(), self)?;
+            let _ = self.journal_state.set(state);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "journal")]
+    pub(crate) fn recover(&self, path: &Path) -> Result<u64, Error> {
+        self.journal
+            .as_ref()
+            .ok_or(Error::InvalidConfig(
+                "event-bus was not built with a journal to decode events with",
+            ))?
+            .recover(self, path)
+    }
+
+    #[cfg(feature = "journal")]
+    pub(crate) fn sync_journal(&self) -> Result<(), Error> {
+        match self.journal_state.get() {
+            Some(state) => state.wait_for_sync(self.sequencer.get()),
+            None => Err(Error::InvalidConfig(
+                "event-bus was not built with a journal",
+            )),
+        }
+    }
+
+    pub(crate) fn start_position(&self) -> StartPosition {
+        self.start_position
+    }
+
+    pub(crate) fn sequencer(&self) -> &Sequencer {
+        &self.sequencer
     }
 
     pub(crate) fn next(&self) -> u64 {
         self.sequencer.next()
     }
 
-    #[cfg(feature = "async")]
-    pub(crate) async fn async_next(&self) -> u64 {
-        self.sequencer.async_next().await
+    pub(crate) fn try_next(&self) -> Result<u64, Error> {
+        self.sequencer.try_next_from(1)
+    }
+
+    pub(crate) fn next_timeout(&self, timeout: Duration) -> Result<u64, Error> {
+        self.sequencer.next_from_timeout(1, timeout)
+    }
+
+    /// Returns the inclusive range of sequences a subscriber may be positioned at: from the oldest
+    /// event that is still held in the ring, up to the next sequence that will be published.
+    pub(crate) fn readable_window(&self) -> (u64, u64) {
+        let cursor = self.sequencer.get();
+        let oldest = if cursor >= self.capacity {
+            cursor - self.capacity + 1
+        } else {
+            1
+        };
+
+        (oldest, cursor + 1)
+    }
+
+    pub(crate) fn seek(&self, sequence: &Sequence, target: u64) -> Result<(), Error> {
+        let (oldest, next) = self.readable_window();
+
+        if target == 0 || target > next {
+            Err(Error::InvalidSequence(target))
+        } else if target < oldest {
+            Err(Error::Lagged(target))
+        } else {
+            self.sequencer.reposition(sequence, target);
+            Ok(())
+        }
+    }
+
+    /// Returns the sequence a new subscriber starting at `position` should read first.
+    pub(crate) fn start_sequence(&self, position: StartPosition) -> u64 {
+        let (oldest, next) = self.readable_window();
+
+        match position {
+            StartPosition::Next => next,
+            StartPosition::Latest => std::cmp::max(next - 1, 1),
+            StartPosition::Earliest => oldest,
+        }
+    }
+
+    /// Writes an event into its claimed sequence in the ring.
+    pub(crate) fn write<T: 'static + Send + Sync>(&self, sequence: u64, event: T) {
+        self.write_with(sequence, event, Metadata::default());
+    }
+
+    /// Writes an event into its claimed sequence in the ring, along with its [`Metadata`].
+    pub(crate) fn write_with<T: 'static + Send + Sync>(
+        &self,
+        sequence: u64,
+        event: T,
+        mut metadata: Metadata,
+    ) {
+        let envelope = self
+            .get_envelope(sequence)
+            .expect("ring buffer was not pre-populated with empty event envelopes");
+
+        if self.track_latency || self.timestamp_events.load(Ordering::Relaxed) {
+            metadata.published_at = latency::now();
+        }
+
+        if metadata.origin == 0 {
+            metadata.origin = self.id;
+        }
+
+        let event = AnyEvent::new(event);
+        let type_id = event.event_type_id();
+        let type_name = event.type_name();
+
+        envelope.overwrite(sequence, event, metadata);
+        self.sequencer.alert_waiters(sequence);
+        self.sequencer.counters().record_publish(type_id, type_name);
+
+        if let Some(hooks) = self.hooks() {
+            hooks.on_publish(sequence, type_id);
+        }
+    }
+
+    /// Makes publishers timestamp every event from now on, whether or not latency is tracked.
+    pub(crate) fn timestamp_events(&self) {
+        self.timestamp_events.store(true, Ordering::Relaxed);
+    }
+
+    /// Records that a subscriber received the event at `sequence`. This must be called before the
+    /// subscriber moves past the sequence, so that the envelope cannot have been overwritten yet.
+    pub(crate) fn record_receive(
+        &self,
+        subscriber: &GatingSequence,
+        sequence: u64,
+        envelope: &EventEnvelope,
+    ) {
+        self.record_receive_at(subscriber, sequence, envelope.published_at());
+    }
+
+    /// Records that a subscriber received the event at `sequence`, which was published at
+    /// `published_at`, after it was taken out of the ring.
+    pub(crate) fn record_receive_at(
+        &self,
+        subscriber: &GatingSequence,
+        sequence: u64,
+        published_at: u64,
+    ) {
+        subscriber.set_received_published_at(published_at);
+
+        if published_at != 0 && self.track_latency {
+            let latency = latency::now().saturating_sub(published_at);
+
+            if let Some(recorder) = subscriber.latency() {
+                recorder.record(latency);
+            }
+
+            #[cfg(feature = "metrics")]
+            self.metrics.record_latency(latency);
+        }
+
+        if let Some(hooks) = self.hooks() {
+            hooks.on_receive(subscriber.id(), sequence);
+        }
+    }
+
+    /// Records that a subscriber found the event at `sequence` overwritten by `envelope_sequence`
+    /// and skipped ahead to it. Only the skipped events that were overwritten are lag, and their
+    /// number is returned.
+    pub(crate) fn record_overwritten(
+        &self,
+        subscriber_id: u64,
+        sequence: u64,
+        envelope_sequence: u64,
+    ) -> u64 {
+        let (oldest, _) = self.readable_window();
+        let overwritten = oldest.clamp(sequence + 1, envelope_sequence) - sequence;
+
+        self.record_lag(subscriber_id, overwritten);
+        overwritten
+    }
+
+    /// Records that a subscriber skipped over `skipped` events that were overwritten before it
+    /// could read them.
+    pub(crate) fn record_lag(&self, subscriber_id: u64, skipped: u64) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            subscriber_id,
+            skipped,
+            "subscriber skipped overwritten events"
+        );
+
+        self.sequencer.counters().record_lag_drops(skipped);
+
+        if let Some(hooks) = self.hooks() {
+            hooks.on_lag(subscriber_id, skipped);
+        }
+    }
+
+    /// Records that a handler could not handle the event at `sequence`, and dead-lettered it.
+    pub(crate) fn record_dead_letter(&self, handler_id: u64, sequence: u64) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(handler_id, sequence, "handler dead-lettered an event");
+
+        self.sequencer.counters().record_dead_letter();
+
+        if let Some(hooks) = self.hooks() {
+            hooks.on_dead_letter(handler_id, sequence);
+        }
+    }
+
+    pub(crate) fn stats(&self) -> Stats {
+        let cursor = self.sequencer.get();
+        let gating_sequences = self.sequencer.gating_sequences();
+        let counters = self.sequencer.counters();
+
+        let mut latency = if self.track_latency {
+            Some(LatencyHistogram::new())
+        } else {
+            None
+        };
+
+        let subscribers: Vec<SubscriberStats> = gating_sequences
+            .iter()
+            .map(|gating_sequence| {
+                if let (Some(latency), Some(recorder)) = (&mut latency, gating_sequence.latency()) {
+                    latency.merge(&recorder.snapshot());
+                }
+
+                let sequence = gating_sequence.get();
+
+                SubscriberStats {
+                    id: gating_sequence.id(),
+                    sequence,
+                    lag: (cursor + 1).saturating_sub(sequence),
+                }
+            })
+            .collect();
+
+        let min_gating_sequence = subscribers
+            .iter()
+            .map(|subscriber| subscriber.sequence)
+            .min()
+            .unwrap_or(cursor + 1);
+
+        Stats {
+            name: self.name.clone(),
+            capacity: self.capacity,
+            cursor,
+            min_gating_sequence,
+            occupancy: std::cmp::min(
+                (cursor + 1).saturating_sub(min_gating_sequence),
+                self.capacity,
+            ),
+            subscriber_count: subscribers.len(),
+            subscribers,
+            published: counters.published(),
+            overwrites: counters.overwrites(),
+            lag_drops: counters.lag_drops(),
+            dead_letters: counters.dead_letters(),
+            publisher_wait: counters.publisher_wait(),
+            latency,
+        }
+    }
+
+    pub(crate) fn skip_to_latest(&self, sequence: &Sequence) -> u64 {
+        let latest = std::cmp::max(self.sequencer.get(), 1);
+        let target = std::cmp::max(sequence.get(), latest);
+
+        self.sequencer.reposition(sequence, target);
+        target
+    }
+
+    /// Moves a subscriber back by `n` sequences, where rewinding past the first sequence is an
+    /// [`Error::InvalidSequence`] rather than lag.
+    pub(crate) fn rewind(&self, sequence: &Sequence, n: u64) -> Result<u64, Error> {
+        let target = sequence.get().saturating_sub(n);
+
+        self.seek(sequence, target)?;
+        Ok(target)
     }
 
     pub(crate) fn idx_from_sequence(&self, sequence: u64) -> usize {
@@ -60,11 +415,7 @@ impl RingBuffer {
     pub(crate) fn get_envelope(&self, sequence: u64) -> Option<EventWrapper> {
         let idx = self.idx_from_sequence(sequence);
 
-        if let Some(envelope) = self.buffer.get(idx).clone() {
-            Some(envelope.clone())
-        } else {
-            None
-        }
+        self.buffer.get(idx).cloned()
     }
 }
 
@@ -77,15 +428,31 @@ impl Default for RingBuffer {
 #[cfg(test)]
 mod tests {
     use crate::ring_buffer::RingBuffer;
+    use crate::Error;
     use crate::WaitStrategy;
 
     #[test]
     fn error_if_not_power_of_two() {
-        assert!(RingBuffer::new(3, WaitStrategy::AllSubscribers).is_err());
+        assert_eq!(
+            Some(Error::InvalidCapacity(3)),
+            RingBuffer::new(3, WaitStrategy::AllSubscribers).err()
+        );
     }
 
     #[test]
     fn success_if_power_of_two() {
         assert!(RingBuffer::new(16, WaitStrategy::AllSubscribers).is_ok());
     }
+
+    #[test]
+    fn readable_window_tracks_cursor() {
+        let ring = RingBuffer::new(4, WaitStrategy::NoWait).unwrap();
+        assert_eq!((1, 1), ring.readable_window());
+
+        for _ in 0..6 {
+            ring.next();
+        }
+
+        assert_eq!((3, 7), ring.readable_window());
+    }
 }
@@ -0,0 +1,435 @@
+use crate::error::Error;
+use crate::ring_buffer::RingBuffer;
+use crate::sequence::sequencer::Sequencer;
+use crate::wait_strategy::WaitStrategy;
+use crossbeam::sync::{Parker, Unparker};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// The resolution of the timer wheel. Events are published at the first tick at or after the time
+/// they are due.
+const TICK: Duration = Duration::from_millis(1);
+
+/// The number of slots in the timer wheel. Events that are due further out than one turn of the
+/// wheel wait in their slot for the turns in between.
+const SLOTS: u64 = 1024;
+
+const PENDING: u8 = 0;
+const PUBLISHED: u8 = 1;
+const CANCELLED: u8 = 2;
+
+/// The scheduler is attempting to claim a sequence for the event, which never blocks.
+const FIRING: u8 = 3;
+
+type Publish = Box<dyn FnOnce(&RingBuffer, u64) + Send>;
+
+/// A handle to an event that was scheduled with [`Eventador::publish_after`] or
+/// [`Eventador::publish_at`].
+///
+/// Dropping the handle does not cancel the event.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(4)?;
+///
+/// let timeout = eventbus.publish_after(Duration::from_secs(30), OrderTimedOut(id))?;
+///
+/// // the order was filled in time
+/// timeout.cancel();
+/// ```
+///
+/// [`Eventador::publish_after`]: crate::Eventador::publish_after
+/// [`Eventador::publish_at`]: crate::Eventador::publish_at
+///
+pub struct ScheduledEvent {
+    state: Arc<AtomicU8>,
+    scheduler: Weak<SchedulerState>,
+    id: u64,
+    due: u64,
+}
+
+impl ScheduledEvent {
+    /// Cancels the event so that it is never published, and drops it.
+    ///
+    /// An event that came due but is still waiting on lagging subscribers can be cancelled as
+    /// well. Returns `false` if the event was already published, or cancelled before.
+    pub fn cancel(&self) -> bool {
+        loop {
+            match self.state.compare_exchange(
+                PENDING,
+                CANCELLED,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break,
+                Err(FIRING) => std::hint::spin_loop(),
+                Err(_) => return false,
+            }
+        }
+
+        if let Some(scheduler) = self.scheduler.upgrade() {
+            scheduler.wheel.lock().unwrap().remove(self.id, self.due);
+        }
+
+        true
+    }
+
+    /// Returns whether the event is still waiting to be published.
+    pub fn is_pending(&self) -> bool {
+        matches!(self.state.load(Ordering::Acquire), PENDING | FIRING)
+    }
+}
+
+struct Timer {
+    id: u64,
+    due: u64,
+    state: Arc<AtomicU8>,
+    publish: Publish,
+}
+
+impl Timer {
+    /// Publishes the event once a sequence can be claimed for it, unless it is cancelled first.
+    ///
+    /// Like [`Sequencer::next_from_timeout`], this waits on lagging subscribers according to the
+    /// wait-strategy. The timer is only marked as published once a sequence is claimed, and each
+    /// claim is attempted without blocking, so the event can be cancelled while it waits.
+    fn fire(self, ring: &RingBuffer) {
+        let sequencer = ring.sequencer();
+        let mut blocked_since = None;
+
+        loop {
+            if self
+                .state
+                .compare_exchange(PENDING, FIRING, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                return;
+            }
+
+            let sequence = match (sequencer.claim(1, blocked_since), sequencer.wait_strategy()) {
+                (Ok(Some(sequence)), _) => Some(sequence),
+
+                (_, WaitStrategy::WaitForDuration(wait))
+                    if blocked_since.is_some_and(|since: Instant| since.elapsed() >= wait) =>
+                {
+                    Some(sequencer.force_claim(1))
+                }
+
+                _ => None,
+            };
+
+            if let Some(sequence) = sequence {
+                self.state.store(PUBLISHED, Ordering::Release);
+                sequencer.record_wait(blocked_since);
+                (self.publish)(ring, sequence);
+
+                return;
+            }
+
+            self.state.store(PENDING, Ordering::Release);
+            Sequencer::start_waiting(&mut blocked_since, Instant::now);
+            sequencer.backoff().wait();
+        }
+    }
+}
+
+/// A hashed timer wheel, whose slots each hold the timers that are due on the ticks that map to
+/// them.
+struct TimerWheel {
+    slots: Vec<Vec<Timer>>,
+    started: Instant,
+
+    /// The last tick that timers were collected for.
+    tick: u64,
+    len: usize,
+    next_id: u64,
+}
+
+impl TimerWheel {
+    fn new(started: Instant) -> Self {
+        Self {
+            slots: (0..SLOTS).map(|_| Vec::new()).collect(),
+            started,
+            tick: 0,
+            len: 0,
+            next_id: 0,
+        }
+    }
+
+    /// Inserts a timer, and returns its id and the tick it is due on.
+    fn insert(&mut self, deadline: Instant, state: Arc<AtomicU8>, publish: Publish) -> (u64, u64) {
+        let since = deadline.saturating_duration_since(self.started).as_nanos();
+        let due = std::cmp::max(since.div_ceil(TICK.as_nanos()) as u64, self.tick + 1);
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.slots[(due % SLOTS) as usize].push(Timer {
+            id,
+            due,
+            state,
+            publish,
+        });
+        self.len += 1;
+
+        (id, due)
+    }
+
+    /// Removes a timer that has not been collected yet.
+    fn remove(&mut self, id: u64, due: u64) {
+        let slot = &mut self.slots[(due % SLOTS) as usize];
+
+        if let Some(i) = slot.iter().position(|timer| timer.id == id) {
+            slot.swap_remove(i);
+            self.len -= 1;
+        }
+    }
+
+    /// Collects every timer that is due by `now`, in the order they are due.
+    fn advance(&mut self, now: Instant) -> Vec<Timer> {
+        let now_tick =
+            (now.saturating_duration_since(self.started).as_nanos() / TICK.as_nanos()) as u64;
+        let mut due = Vec::new();
+
+        if self.len > 0 {
+            // A whole turn of the wheel visits every slot, however long it has been
+            let ticks = std::cmp::min(now_tick.saturating_sub(self.tick), SLOTS);
+
+            for tick in self.tick + 1..=self.tick + ticks {
+                let slot = &mut self.slots[(tick % SLOTS) as usize];
+
+                let mut i = 0;
+                while i < slot.len() {
+                    if slot[i].due <= now_tick {
+                        due.push(slot.swap_remove(i));
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+
+            self.len -= due.len();
+            due.sort_by_key(|timer| (timer.due, timer.id));
+        }
+
+        self.tick = std::cmp::max(self.tick, now_tick);
+        due
+    }
+
+    /// Get how long until the next timer may be due, or `None` if there are no timers.
+    fn next_wait(&self, now: Instant) -> Option<Duration> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // The first slot that holds any timers is a lower bound, since its timers may be due on a
+        // later turn of the wheel
+        let tick = (self.tick + 1..=self.tick + SLOTS)
+            .find(|tick| !self.slots[(tick % SLOTS) as usize].is_empty())
+            .unwrap_or(self.tick + 1);
+
+        let at = self.started + Duration::from_nanos(TICK.as_nanos() as u64 * tick);
+        Some(at.saturating_duration_since(now))
+    }
+}
+
+struct SchedulerState {
+    wheel: Mutex<TimerWheel>,
+    closed: AtomicBool,
+}
+
+/// Publishes scheduled events into a ring when they come due, from a timer wheel that is driven by
+/// a dedicated thread.
+pub(crate) struct Scheduler {
+    state: Arc<SchedulerState>,
+    unparker: Unparker,
+}
+
+impl Scheduler {
+    pub(crate) fn spawn(ring: Weak<RingBuffer>) -> Result<Self, Error> {
+        let state = Arc::new(SchedulerState {
+            wheel: Mutex::new(TimerWheel::new(Instant::now())),
+            closed: AtomicBool::new(false),
+        });
+
+        let parker = Parker::new();
+        let unparker = parker.unparker().clone();
+
+        let thread_state = state.clone();
+        std::thread::Builder::new()
+            .name(String::from("eventador-scheduler"))
+            .spawn(move || run(ring, thread_state, parker))
+            .map_err(|err| Error::Spawn(err.to_string()))?;
+
+        Ok(Self { state, unparker })
+    }
+
+    pub(crate) fn schedule<T: 'static + Send + Sync>(
+        &self,
+        deadline: Instant,
+        message: T,
+    ) -> ScheduledEvent {
+        let state = Arc::new(AtomicU8::new(PENDING));
+
+        let publish = Box::new(move |ring: &RingBuffer, sequence| ring.write(sequence, message));
+
+        let (id, due) = self
+            .state
+            .wheel
+            .lock()
+            .unwrap()
+            .insert(deadline, state.clone(), publish);
+
+        // The new event may be due before the thread planned to wake up
+        self.unparker.unpark();
+
+        ScheduledEvent {
+            state,
+            scheduler: Arc::downgrade(&self.state),
+            id,
+            due,
+        }
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        // The thread is not joined, since it may be the one dropping the ring
+        self.state.closed.store(true, Ordering::Release);
+        self.unparker.unpark();
+    }
+}
+
+fn run(ring: Weak<RingBuffer>, state: Arc<SchedulerState>, parker: Parker) {
+    while !state.closed.load(Ordering::Acquire) {
+        let now = Instant::now();
+
+        let (due, wait) = {
+            let mut wheel = state.wheel.lock().unwrap();
+            let due = wheel.advance(now);
+            (due, wheel.next_wait(now))
+        };
+
+        if !due.is_empty() {
+            let ring = match ring.upgrade() {
+                Some(ring) => ring,
+                None => return,
+            };
+
+            // Publishing waits on the ring's subscribers, which holds back later events as well
+            for timer in due {
+                timer.fire(&ring);
+            }
+        } else {
+            match wait {
+                Some(wait) => parker.park_timeout(wait),
+                None => parker.park(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schedule::*;
+    use crate::Eventador;
+
+    fn timer(wheel: &mut TimerWheel, deadline: Instant) -> Arc<AtomicU8> {
+        let state = Arc::new(AtomicU8::new(PENDING));
+        wheel.insert(deadline, state.clone(), Box::new(|_, _| {}));
+        state
+    }
+
+    #[test]
+    fn collects_timers_in_order() {
+        let started = Instant::now();
+        let mut wheel = TimerWheel::new(started);
+
+        let later = timer(&mut wheel, started + Duration::from_millis(5));
+        let sooner = timer(&mut wheel, started + Duration::from_millis(2));
+        let next_turn = timer(&mut wheel, started + TICK * (SLOTS as u32 + 2));
+
+        assert_eq!(Some(TICK * 2), wheel.next_wait(started));
+        assert!(wheel.advance(started + TICK).is_empty());
+
+        let due = wheel.advance(started + Duration::from_millis(10));
+        assert_eq!(2, due.len());
+        assert!(Arc::ptr_eq(&sooner, &due[0].state));
+        assert!(Arc::ptr_eq(&later, &due[1].state));
+
+        // The timer on the next turn of the wheel is not due when its slot comes round the first
+        // time, but is after a long pause
+        assert!(wheel.advance(started + TICK * SLOTS as u32).is_empty());
+        let due = wheel.advance(started + Duration::from_secs(60));
+        assert!(Arc::ptr_eq(&next_turn, &due[0].state));
+        assert_eq!(None, wheel.next_wait(started));
+    }
+
+    #[test]
+    fn publishes_scheduled_events() {
+        let eventbus = Eventador::new(4).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        let started = Instant::now();
+        let second = eventbus
+            .publish_after(Duration::from_millis(30), 2_usize)
+            .unwrap();
+        let first = eventbus
+            .publish_at(started + Duration::from_millis(10), 1_usize)
+            .unwrap();
+        let cancelled = eventbus
+            .publish_after(Duration::from_millis(20), 3_usize)
+            .unwrap();
+        eventbus
+            .publish_after(Duration::from_millis(40), 4_usize)
+            .unwrap();
+
+        assert!(cancelled.cancel());
+        assert!(!cancelled.cancel());
+
+        // The cancelled event no longer takes up the wheel
+        let scheduler = eventbus.ring.scheduler().unwrap();
+        assert_eq!(3, scheduler.state.wheel.lock().unwrap().len);
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert!(started.elapsed() >= Duration::from_millis(10));
+        assert!(!first.is_pending());
+
+        assert_eq!(2, *subscriber.recv().unwrap());
+        assert!(started.elapsed() >= Duration::from_millis(30));
+        assert!(!second.cancel());
+
+        // The cancelled event was never published
+        assert_eq!(4, *subscriber.recv().unwrap());
+    }
+
+    #[test]
+    fn cancels_events_waiting_on_subscribers() {
+        let eventbus = Eventador::new(2).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        eventbus.publish(1_usize);
+        eventbus.publish(2_usize);
+
+        // The event comes due while the ring is full, and waits on the subscriber
+        let scheduled = eventbus
+            .publish_after(Duration::from_millis(1), 3_usize)
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(scheduled.is_pending());
+
+        assert!(scheduled.cancel());
+        assert!(!scheduled.is_pending());
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert_eq!(2, *subscriber.recv().unwrap());
+
+        eventbus.publish(4_usize);
+        assert_eq!(4, *subscriber.recv().unwrap());
+        assert_eq!(3, eventbus.stats().published_count::<usize>());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::Error;
+use crate::sequence::sequencer::Sequencer;
+use crate::wait_strategy::{Backoff, WaitStrategy};
+use futures::task::{Context, Poll};
+use futures::Future;
+use std::pin::Pin;
+use std::time::Instant;
+
+type Sleep = Pin<Box<dyn Future<Output = ()> + Send + Sync>>;
+
+/// The state of an asynchronous claim of the next sequences from the [`Sequencer`], which is
+/// polled with the sequencer it claims from.
+///
+/// The sequences are claimed within a single poll once they are free, so a claim that is dropped
+/// before it completed has not claimed anything. Callers write to the claimed sequences before
+/// they yield again, so that a claimed sequence is never left unpublished for subscribers to stall
+/// on.
+pub(crate) struct Claim {
+    n: u64,
+    blocked_since: Option<Instant>,
+    sleep: Option<Sleep>,
+}
+
+impl Claim {
+    pub fn new(n: u64) -> Self {
+        Self {
+            n,
+            blocked_since: None,
+            sleep: None,
+        }
+    }
+
+    pub fn poll_claim(
+        &mut self,
+        sequencer: &Sequencer,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<u64, Error>> {
+        loop {
+            if let Some(sleep) = self.sleep.as_mut() {
+                match sleep.as_mut().poll(cx) {
+                    Poll::Ready(()) => self.sleep = None,
+                    Poll::Pending => return Poll::Pending,
+                }
+
+                // The wait-strategy's duration has passed, so the sequences are claimed
+                // regardless of any lagging subscribers
+                if let WaitStrategy::WaitForDuration(_) = sequencer.wait_strategy() {
+                    let sequence = match sequencer.claim(self.n, self.blocked_since)? {
+                        Some(sequence) => sequence,
+                        None => sequencer.force_claim(self.n),
+                    };
+
+                    sequencer.record_wait(self.blocked_since.take());
+                    return Poll::Ready(Ok(sequence));
+                }
+            }
+
+            if let Some(sequence) = sequencer.claim(self.n, self.blocked_since)? {
+                sequencer.record_wait(self.blocked_since.take());
+                return Poll::Ready(Ok(sequence));
+            }
+
+            Sequencer::start_waiting(&mut self.blocked_since, Instant::now);
+
+            let wait = match sequencer.wait_strategy() {
+                WaitStrategy::WaitForDuration(wait) => wait,
+
+                _ => match sequencer.backoff() {
+                    Backoff::Sleep(duration) => duration,
+
+                    Backoff::Spin | Backoff::Yield => {
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                },
+            };
+
+            self.sleep = Some(Box::pin(async_std::task::sleep(wait)));
+        }
+    }
+}
+
+/// A future that claims the next sequences from the [`Sequencer`], created by
+/// [`Sequencer::async_next_from`].
+///
+/// Waits on lagging subscribers according to the wait-strategy, like [`Sequencer::next_from`].
+/// Dropping the future before it completes never leaves a sequence claimed.
+pub(crate) struct ClaimFuture<'a> {
+    sequencer: &'a Sequencer,
+    claim: Claim,
+}
+
+impl<'a> ClaimFuture<'a> {
+    pub fn new(sequencer: &'a Sequencer, n: u64) -> Self {
+        Self {
+            sequencer,
+            claim: Claim::new(n),
+        }
+    }
+}
+
+impl<'a> Future for ClaimFuture<'a> {
+    type Output = Result<u64, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.claim.poll_claim(this.sequencer, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Eventador, WaitStrategy};
+    use futures::FutureExt;
+    use std::time::Duration;
+
+    #[async_std::test]
+    async fn claims_once_subscribers_catch_up() {
+        let eventbus = Eventador::new(2).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+        let sequencer = eventbus.ring.sequencer();
+
+        eventbus.publish(1_usize);
+        eventbus.publish(2_usize);
+
+        // A claim that is dropped while it waits leaves nothing claimed
+        assert!(sequencer.async_next_from(1).now_or_never().is_none());
+        assert_eq!(2, sequencer.get());
+
+        let claim = sequencer.async_next_from(1);
+        let subscriber_thread = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            assert_eq!(1, *subscriber.recv().unwrap());
+        });
+
+        assert_eq!(Ok(3), claim.await);
+        subscriber_thread.join().unwrap();
+    }
+
+    #[async_std::test]
+    async fn claims_after_waiting_for_duration() {
+        let wait = WaitStrategy::WaitForDuration(Duration::from_millis(10));
+        let eventbus = Eventador::with_strategy(2, wait).unwrap();
+        let _subscriber = eventbus.subscribe::<usize>();
+        let sequencer = eventbus.ring.sequencer();
+
+        eventbus.publish(1_usize);
+        eventbus.publish(2_usize);
+
+        assert_eq!(Ok(3), sequencer.async_next_from(1).await);
+        assert_eq!(1, eventbus.stats().overwrites);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::latency::LatencyRecorder;
+use crate::sequence::Sequence;
+use crate::waiter::Waiter;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A subscriber's [`Sequence`] as registered with the sequencer.
+///
+/// Publishers gate on the wrapped sequence, and the id identifies the subscriber when it is
+/// deregistered or evicted. The subscriber registers with its [`Waiter`] while it waits for the
+/// next event to be published.
+pub struct GatingSequence {
+    id: u64,
+    sequence: Sequence,
+    evicted: AtomicBool,
+    lagged: AtomicU64,
+    received_published_at: AtomicU64,
+    latency: Option<LatencyRecorder>,
+    waiter: Waiter,
+}
+
+impl GatingSequence {
+    pub fn new(id: u64, initial_value: u64, waiting: Arc<AtomicU64>) -> Self {
+        Self {
+            id,
+            sequence: Sequence::with_value(initial_value),
+            evicted: AtomicBool::new(false),
+            lagged: AtomicU64::new(0),
+            received_published_at: AtomicU64::new(0),
+            latency: None,
+            waiter: Waiter::new(waiting),
+        }
+    }
+
+    /// Records the publish-to-read latency of every event the subscriber reads.
+    pub fn with_latency_recorder(mut self) -> Self {
+        self.latency = Some(LatencyRecorder::new());
+        self
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn evict(&self) {
+        self.evicted.store(true, Ordering::Release);
+    }
+
+    pub fn is_evicted(&self) -> bool {
+        self.evicted.load(Ordering::Acquire)
+    }
+
+    /// Counts events that the subscriber skipped over because they were overwritten.
+    pub fn add_lagged(&self, skipped: u64) {
+        self.lagged.fetch_add(skipped, Ordering::Relaxed);
+    }
+
+    /// Get the number of events that the subscriber skipped over because they were overwritten.
+    pub fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+
+    /// Records the publish timestamp of the event the subscriber last received.
+    pub fn set_received_published_at(&self, published_at: u64) {
+        self.received_published_at
+            .store(published_at, Ordering::Relaxed);
+    }
+
+    /// Get the publish timestamp of the event the subscriber last received, or zero if it was
+    /// published without one.
+    pub fn received_published_at(&self) -> u64 {
+        self.received_published_at.load(Ordering::Relaxed)
+    }
+
+    pub fn latency(&self) -> Option<&LatencyRecorder> {
+        self.latency.as_ref()
+    }
+
+    pub fn waiter(&self) -> &Waiter {
+        &self.waiter
+    }
+}
+
+impl Deref for GatingSequence {
+    type Target = Sequence;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sequence
+    }
+}
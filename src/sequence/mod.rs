@@ -1,3 +1,6 @@
+#[cfg(feature = "async")]
+pub(crate) mod claim;
+pub(crate) mod gating_sequence;
 pub(crate) mod sequence_group;
 pub(crate) mod sequencer;
 
@@ -48,7 +51,7 @@ impl Ord for Sequence {
 
 impl PartialOrd for Sequence {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.get().cmp(&other.get()))
+        Some(self.cmp(other))
     }
 }
 
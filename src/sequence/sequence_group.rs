@@ -1,37 +1,41 @@
-use crate::sequence::Sequence;
-use lockfree::set::{Removed, Set};
+use crate::sequence::gating_sequence::GatingSequence;
+use lockfree::map::Map;
 use std::sync::Arc;
 
 pub struct SequenceGroup {
-    set: Set<Arc<Sequence>>,
+    map: Map<u64, Arc<GatingSequence>>,
 }
 
 #[allow(dead_code)]
 impl SequenceGroup {
     pub fn new() -> Self {
-        Self { set: Set::new() }
+        Self { map: Map::new() }
     }
 
-    pub fn add(&self, sequence: Arc<Sequence>) -> bool {
-        self.set.insert(sequence).is_ok()
+    pub fn add(&self, sequence: Arc<GatingSequence>) -> bool {
+        self.map.insert(sequence.id(), sequence).is_none()
     }
 
-    pub fn remove(&self, sequence: Arc<Sequence>) -> Option<Removed<Arc<Sequence>>> {
-        self.set.remove(&sequence)
+    pub fn remove(&self, id: u64) -> bool {
+        self.map.remove(&id).is_some()
     }
 
     pub fn size(&self) -> usize {
-        self.set.iter().count()
+        self.map.iter().count()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Arc<GatingSequence>> + '_ {
+        self.map.iter().map(|entry| entry.val().clone())
     }
 
     pub fn minimum_sequence(&self, minimum: u64) -> u64 {
         let mut minimum = minimum;
 
-        if let Some(sequence) = self.set.iter().map(|sequence| sequence.get()).min() {
+        if let Some(sequence) = self.map.iter().map(|entry| entry.val().get()).min() {
             minimum = std::cmp::min(minimum, sequence);
         }
 
-        return minimum;
+        minimum
     }
 }
 
@@ -44,14 +48,27 @@ mod tests {
         let sg = SequenceGroup::new();
         assert_eq!(0, sg.size());
 
-        let s1 = Arc::new(Sequence::with_value(1));
+        let s1 = Arc::new(GatingSequence::new(1, 1, Default::default()));
         sg.add(s1);
         assert_eq!(1, sg.size());
 
-        let s2 = Arc::new(Sequence::with_value(5));
+        let s2 = Arc::new(GatingSequence::new(2, 5, Default::default()));
         sg.add(s2);
         assert_eq!(2, sg.size());
 
         assert_eq!(1, sg.minimum_sequence(100));
     }
+
+    #[test]
+    fn same_position_sequences_are_distinct() {
+        let sg = SequenceGroup::new();
+
+        assert!(sg.add(Arc::new(GatingSequence::new(1, 3, Default::default()))));
+        assert!(sg.add(Arc::new(GatingSequence::new(2, 3, Default::default()))));
+        assert_eq!(2, sg.size());
+
+        assert!(sg.remove(1));
+        assert!(!sg.remove(1));
+        assert_eq!(1, sg.size());
+    }
 }
@@ -1,139 +1,400 @@
+use crate::builder::{EventadorBuilder, ProducerType};
+use crate::error::Error;
+use crate::eviction::{Eviction, EvictionPolicy};
+use crate::hooks::Hooks;
+#[cfg(feature = "async")]
+use crate::sequence::claim::ClaimFuture;
+use crate::sequence::gating_sequence::GatingSequence;
 use crate::sequence::sequence_group::SequenceGroup;
 use crate::sequence::Sequence;
-use crate::WaitStrategy;
+use crate::stats::Counters;
+use crate::wait_strategy::{Backoff, WaitStrategy};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub struct Sequencer {
     cursor: Sequence,
     gating_sequence_cache: Arc<Sequence>,
     gating_sequences: SequenceGroup,
+    next_gating_id: AtomicU64,
+    waiting: Arc<AtomicU64>,
     ring_capacity: u64,
+    producer_type: ProducerType,
     wait_strategy: WaitStrategy,
+    backoff: Backoff,
+    eviction_policy: Option<EvictionPolicy>,
+    hooks: Option<Arc<dyn Hooks>>,
+    track_latency: bool,
+    counters: Counters,
 }
 
 impl Sequencer {
-    pub fn new(ring_capacity: u64, wait_strategy: WaitStrategy) -> Self {
+    pub fn new(builder: &EventadorBuilder) -> Self {
         Self {
             cursor: Sequence::with_value(0),
             gating_sequence_cache: Arc::new(Sequence::with_value(0)),
             gating_sequences: SequenceGroup::new(),
-            ring_capacity,
-            wait_strategy,
+            next_gating_id: AtomicU64::new(1),
+            waiting: Arc::new(AtomicU64::new(0)),
+            ring_capacity: builder.capacity,
+            producer_type: builder.producer_type,
+            wait_strategy: builder.wait_strategy,
+            backoff: builder.backoff,
+            eviction_policy: builder.eviction_policy.clone(),
+            hooks: builder.hooks.clone(),
+            track_latency: builder.track_latency,
+            counters: Counters::new(),
         }
     }
 
-    pub(crate) fn register_gating_sequence(&self, sequence: Arc<Sequence>) {
-        self.gating_sequences.add(sequence);
+    /// Registers a new gating sequence that starts at `initial_value`.
+    pub(crate) fn register_gating_sequence(&self, initial_value: u64) -> Arc<GatingSequence> {
+        let id = self.next_gating_id.fetch_add(1, Ordering::Relaxed);
+        let mut sequence = GatingSequence::new(id, initial_value, self.waiting.clone());
+        if self.track_latency {
+            sequence = sequence.with_latency_recorder();
+        }
+
+        let sequence = Arc::new(sequence);
+
+        self.gating_sequences.add(sequence.clone());
+        self.reposition(&sequence, initial_value);
+
+        sequence
+    }
+
+    pub(crate) fn deregister_gating_sequence(&self, sequence: &GatingSequence) {
+        self.gating_sequences.remove(sequence.id());
     }
 
     pub fn get(&self) -> u64 {
         self.cursor.get()
     }
 
+    /// Alerts every subscriber that is waiting on a sequence up to `published`.
+    ///
+    /// Subscribers are alerted without waiting on them or taking any lock, so a subscriber that
+    /// never reads again can't hold back the publisher. Publishing only checks the registrations
+    /// while subscribers are waiting, at the cost of a load per subscriber.
+    pub(crate) fn alert_waiters(&self, published: u64) {
+        // Pairs with the fence in `Waiter::wait_for`, so that either a waiting subscriber is seen
+        // here, or it sees the published sequence
+        std::sync::atomic::fence(Ordering::SeqCst);
+
+        if self.waiting.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+
+        for gating_sequence in self.gating_sequences.iter() {
+            gating_sequence.waiter().alert(published);
+        }
+    }
+
+    pub(crate) fn producer_type(&self) -> ProducerType {
+        self.producer_type
+    }
+
+    pub(crate) fn wait_strategy(&self) -> WaitStrategy {
+        self.wait_strategy
+    }
+
+    /// How claims that can't block the calling thread back off between attempts, shared by
+    /// [`Claim::poll_claim`](crate::sequence::claim::Claim) and the scheduler's timers. Only the
+    /// latter is built without the `async` feature, so this isn't gated on it.
+    pub(crate) fn backoff(&self) -> Backoff {
+        self.backoff
+    }
+
+    pub(crate) fn counters(&self) -> &Counters {
+        &self.counters
+    }
+
+    pub(crate) fn gating_sequences(&self) -> &SequenceGroup {
+        &self.gating_sequences
+    }
+
+    /// Moves a gating sequence to a new position.
+    ///
+    /// The cached gating sequence is lowered if the new position is behind it, so that publishers
+    /// re-check the gating sequences before wrapping over the events the subscriber moved back to.
+    pub(crate) fn reposition(&self, sequence: &Sequence, target: u64) {
+        sequence.set(target);
+
+        if target < self.gating_sequence_cache.get() {
+            self.gating_sequence_cache.set(target);
+        }
+    }
+
     pub fn next(&self) -> u64 {
         self.next_from(1)
             .expect("sequencer could not get next sequence number from sequence 1")
     }
 
-    #[cfg(feature = "async")]
-    pub async fn async_next(&self) -> u64 {
-        self.async_next_from(1)
-            .await
-            .expect("sequencer could not get next sequence number from sequence 1")
+    /// The distance behind the next sequence at which publishers re-check the gating sequences.
+    fn gating_distance(&self) -> u64 {
+        match self
+            .eviction_policy
+            .as_ref()
+            .and_then(EvictionPolicy::lag_limit)
+        {
+            Some(max_lag) => std::cmp::min(max_lag, self.ring_capacity),
+            None => self.ring_capacity,
+        }
     }
 
-    pub fn next_from(&self, n: u64) -> anyhow::Result<u64> {
-        if n < 1 || n > self.ring_capacity {
-            return Err(anyhow::Error::msg("n must be > 0 and < buffer_size"));
+    /// Finds the minimum gating sequence, after evicting any subscribers that violate the
+    /// eviction policy.
+    fn gating_sequence(
+        &self,
+        current: u64,
+        wrap_point: i64,
+        blocked_since: Option<Instant>,
+    ) -> u64 {
+        if let Some(policy) = &self.eviction_policy {
+            let stalled_for = blocked_since.map(|since| since.elapsed());
+            self.evict(policy, current, wrap_point, stalled_for);
         }
 
-        loop {
-            std::hint::spin_loop();
+        self.gating_sequences.minimum_sequence(current)
+    }
 
-            let current: u64 = self.cursor.get();
-            let icurrent: i64 = current as i64;
-            let next: i64 = (current + n) as i64;
+    fn evict(
+        &self,
+        policy: &EvictionPolicy,
+        current: u64,
+        wrap_point: i64,
+        stalled_for: Option<Duration>,
+    ) {
+        for gating_sequence in self.gating_sequences.iter() {
+            let sequence = gating_sequence.get();
+            let lag = (current + 1).saturating_sub(sequence);
 
-            let wrap_point: i64 = next - self.ring_capacity as i64;
-            let cached_gating_sequence: i64 = self.gating_sequence_cache.get() as i64;
+            // Only subscribers that are holding back the publisher have stalled it
+            let stalled_for = stalled_for.filter(|_| sequence as i64 <= wrap_point);
+
+            if let Some(reason) = policy.should_evict(lag, stalled_for) {
+                if self.gating_sequences.remove(gating_sequence.id()) {
+                    gating_sequence.evict();
+                    gating_sequence.waiter().alert(u64::MAX);
 
-            if wrap_point >= cached_gating_sequence || cached_gating_sequence > icurrent {
-                let gating_sequence = self.gating_sequences.minimum_sequence(current);
-
-                match self.wait_strategy {
-                    WaitStrategy::AllSubscribers => {
-                        if wrap_point > gating_sequence as i64 {
-                            std::thread::sleep(std::time::Duration::from_micros(100));
-                            continue;
-                        }
-                    }
-
-                    WaitStrategy::NoWait => {
-                        if self.cursor.compare_exchange(current, next as u64) {
-                            return Ok(next as u64);
-                        }
-                    }
-
-                    WaitStrategy::WaitForDuration(wait) => {
-                        if self.cursor.compare_exchange(current, next as u64) {
-                            std::thread::sleep(wait);
-
-                            return Ok(next as u64);
-                        }
-                    }
+                    policy.notify(&Eviction {
+                        subscriber_id: gating_sequence.id(),
+                        sequence,
+                        lag,
+                        reason,
+                    });
                 }
+            }
+        }
+    }
 
-                self.gating_sequence_cache.set(gating_sequence);
-            } else if self.cursor.compare_exchange(current, next as u64) {
-                return Ok(next as u64);
+    pub fn next_from(&self, n: u64) -> Result<u64, Error> {
+        let mut blocked_since = None;
+
+        loop {
+            if let Some(sequence) = self.claim(n, blocked_since)? {
+                self.record_wait(blocked_since);
+                return Ok(sequence);
+            }
+
+            match self.wait_strategy {
+                WaitStrategy::WaitForDuration(wait) => {
+                    let sequence = self.force_claim(n);
+                    std::thread::sleep(wait);
+
+                    return Ok(sequence);
+                }
+
+                _ => {
+                    Self::start_waiting(&mut blocked_since, Instant::now);
+                    self.backoff.wait();
+                }
             }
         }
     }
 
+    /// Claims the next `n` sequences asynchronously, waiting on lagging subscribers like
+    /// [`Sequencer::next_from`].
     #[cfg(feature = "async")]
-    pub async fn async_next_from(&self, n: u64) -> anyhow::Result<u64> {
+    pub fn async_next_from(&self, n: u64) -> ClaimFuture<'_> {
+        ClaimFuture::new(self, n)
+    }
+
+    /// Attempts to claim the next `n` sequences without waiting on lagging subscribers.
+    ///
+    /// Returns [`Error::Full`] if the claim would overwrite events that subscribers have not read
+    /// yet, and the wait-strategy would have the publisher wait for them.
+    pub fn try_next_from(&self, n: u64) -> Result<u64, Error> {
+        self.claim(n, None)?.ok_or(Error::Full)
+    }
+
+    /// Claims the next `n` sequences, waiting on lagging subscribers for at most `timeout`.
+    ///
+    /// Returns [`Error::Timeout`] if the sequences could not be claimed in time. With
+    /// [`WaitStrategy::WaitForDuration`], the sequences are claimed once the strategy's duration
+    /// has elapsed, as long as that is within the timeout.
+    pub fn next_from_timeout(&self, n: u64, timeout: Duration) -> Result<u64, Error> {
+        let started = Instant::now();
+        let mut blocked_since = None;
+
+        loop {
+            if let Some(sequence) = self.claim(n, blocked_since)? {
+                self.record_wait(blocked_since);
+                return Ok(sequence);
+            }
+
+            let waited = started.elapsed();
+            if let WaitStrategy::WaitForDuration(wait) = self.wait_strategy {
+                if waited >= wait && wait <= timeout {
+                    return Ok(self.force_claim(n));
+                }
+            }
+
+            if waited >= timeout {
+                return Err(Error::Timeout);
+            }
+
+            Self::start_waiting(&mut blocked_since, || started);
+            match self.backoff {
+                Backoff::Sleep(duration) => {
+                    std::thread::sleep(std::cmp::min(duration, timeout - waited))
+                }
+
+                backoff => backoff.wait(),
+            }
+        }
+    }
+
+    /// Marks the publisher as blocked on lagging subscribers, if it wasn't already.
+    pub(crate) fn start_waiting(
+        blocked_since: &mut Option<Instant>,
+        since: impl FnOnce() -> Instant,
+    ) {
+        if blocked_since.is_none() {
+            #[cfg(feature = "tracing")]
+            tracing::trace!("publisher waiting on lagging subscribers");
+
+            *blocked_since = Some(since());
+        }
+    }
+
+    pub(crate) fn record_wait(&self, blocked_since: Option<Instant>) {
+        if let Some(blocked_since) = blocked_since {
+            let waited = blocked_since.elapsed();
+            self.counters.record_publisher_wait(waited);
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                waited_us = waited.as_micros() as u64,
+                "publisher stopped waiting"
+            );
+
+            if let Some(hooks) = &self.hooks {
+                hooks.on_publisher_wait(waited);
+            }
+        }
+    }
+
+    pub(crate) fn claim(
+        &self,
+        n: u64,
+        blocked_since: Option<Instant>,
+    ) -> Result<Option<u64>, Error> {
         if n < 1 || n > self.ring_capacity {
-            return Err(anyhow::Error::msg("n must be > 0 and < buffer_size"));
+            return Err(Error::InvalidBatchSize(n));
         }
 
+        let gating_distance = self.gating_distance() as i64;
+
         loop {
+            std::hint::spin_loop();
+
             let current: u64 = self.cursor.get();
             let icurrent: i64 = current as i64;
             let next: i64 = (current + n) as i64;
 
             let wrap_point: i64 = next - self.ring_capacity as i64;
             let cached_gating_sequence: i64 = self.gating_sequence_cache.get() as i64;
+            let mut overwritten = 0;
+
+            if next - gating_distance >= cached_gating_sequence || cached_gating_sequence > icurrent
+            {
+                let gating_sequence = self.gating_sequence(current, wrap_point, blocked_since);
+                self.gating_sequence_cache.set(gating_sequence);
 
-            if wrap_point >= cached_gating_sequence || cached_gating_sequence > icurrent {
-                let gating_sequence = self.gating_sequences.minimum_sequence(current);
-
-                match self.wait_strategy {
-                    WaitStrategy::AllSubscribers => {
-                        if wrap_point > gating_sequence as i64 {
-                            async_std::task::sleep(std::time::Duration::from_micros(100)).await;
-                            continue;
-                        }
-                    }
-
-                    WaitStrategy::NoWait => {
-                        if self.cursor.compare_exchange(current, next as u64) {
-                            return Ok(next as u64);
-                        }
-                    }
-
-                    WaitStrategy::WaitForDuration(wait) => {
-                        if self.cursor.compare_exchange(current, next as u64) {
-                            async_std::task::sleep(wait).await;
-
-                            return Ok(next as u64);
-                        }
-                    }
+                let overwrites_unread = wrap_point >= gating_sequence as i64;
+                if overwrites_unread && !matches!(self.wait_strategy, WaitStrategy::NoWait) {
+                    return Ok(None);
                 }
 
-                self.gating_sequence_cache.set(gating_sequence);
-            } else if self.cursor.compare_exchange(current, next as u64) {
-                return Ok(next as u64);
+                overwritten = self.unread_in_claim(current, wrap_point, gating_sequence);
+            }
+
+            let claimed = if self.producer_type == ProducerType::Single {
+                self.cursor.set(next as u64);
+                true
+            } else {
+                self.cursor.compare_exchange(current, next as u64)
+            };
+
+            if claimed {
+                #[cfg(feature = "tracing")]
+                tracing::trace!(sequence = next, n, "claimed sequences");
+
+                if overwritten > 0 {
+                    self.record_overwrites(overwritten);
+                }
+
+                return Ok(Some(next as u64));
             }
         }
     }
+
+    /// Counts the unread events that claiming up to `wrap_point` places ahead of `current` would
+    /// overwrite, excluding any that earlier claims already overwrote.
+    fn unread_in_claim(&self, current: u64, wrap_point: i64, gating_sequence: u64) -> u64 {
+        let first_overwritten = current as i64 + 1 - self.ring_capacity as i64;
+        let oldest_unread = std::cmp::max(first_overwritten, gating_sequence as i64);
+
+        std::cmp::max(wrap_point - oldest_unread + 1, 0) as u64
+    }
+
+    /// Claims the next `n` sequences regardless of any lagging subscribers.
+    pub(crate) fn force_claim(&self, n: u64) -> u64 {
+        let current = if self.producer_type == ProducerType::Single {
+            let current = self.cursor.get();
+            self.cursor.set(current + n);
+
+            current
+        } else {
+            loop {
+                let current = self.cursor.get();
+
+                if self.cursor.compare_exchange(current, current + n) {
+                    break current;
+                }
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(sequence = current + n, n, "force-claimed sequences");
+
+        let wrap_point = (current + n) as i64 - self.ring_capacity as i64;
+        let gating_sequence = self.gating_sequences.minimum_sequence(current);
+        let overwritten = self.unread_in_claim(current, wrap_point, gating_sequence);
+
+        if overwritten > 0 {
+            self.record_overwrites(overwritten);
+        }
+
+        current + n
+    }
+
+    fn record_overwrites(&self, overwritten: u64) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(overwritten, "overwrote unread events");
+
+        self.counters.record_overwrites(overwritten);
+    }
 }
@@ -0,0 +1,937 @@
+use crate::error::Error;
+use crate::publisher::TrySendError;
+use crate::wait_strategy::Backoff;
+use bytemuck::Pod;
+use memmap2::MmapMut;
+use std::fs::{File, OpenOptions};
+use std::marker::PhantomData;
+use std::mem::{align_of, size_of};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const MAGIC: u64 = u64::from_le_bytes(*b"EVTADOR1");
+const VERSION: u64 = 1;
+
+const MAX_PUBLISHERS: usize = 32;
+const MAX_SUBSCRIBERS: usize = 64;
+
+/// Marks a slot whose publisher crashed after claiming it, so that subscribers skip over it.
+const SKIPPED: u64 = 1 << 63;
+
+/// How often a waiting publisher or subscriber checks for crashed attachers.
+const REAP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to wait for the creator of a ring to finish initializing it.
+const ATTACH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Keeps each value on its own cache-line. Unlike `CachePadded`, the layout is `#[repr(C)]`, so
+/// that it is the same in every process that maps the ring.
+#[repr(C, align(64))]
+struct Padded<T>(T);
+
+/// A publisher or subscriber that is attached to the ring.
+///
+/// For a subscriber, `sequence` is the next sequence it will read. For a publisher, it is the
+/// sequence it is claiming or has claimed but not yet published, or zero.
+///
+/// `started` and `namespace` identify the process along with its `pid`, and are zero while the
+/// entry is being taken.
+#[repr(C)]
+struct Attacher {
+    pid: AtomicU64,
+    started: AtomicU64,
+    namespace: AtomicU64,
+    sequence: AtomicU64,
+}
+
+impl Attacher {
+    fn process(&self, pid: u64) -> Process {
+        // The namespace is stored last when the entry is taken, and cleared first when it is freed
+        let namespace = self.namespace.load(Ordering::Acquire);
+
+        Process {
+            pid,
+            started: self.started.load(Ordering::Acquire),
+            namespace,
+        }
+    }
+
+    /// Frees the entry of the process `pid`, unless it has already been freed and taken by
+    /// another attacher.
+    fn release(&self, pid: u64) -> bool {
+        self.namespace.store(0, Ordering::Release);
+        self.started.store(0, Ordering::Release);
+        self.sequence.store(0, Ordering::Release);
+
+        self.pid
+            .compare_exchange(pid, 0, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+    }
+}
+
+/// Identifies an attached process by its pid, along with the time it started and its pid
+/// namespace, so that a process that reuses the pid of a crashed attacher, or has the same pid in
+/// another namespace, is not mistaken for it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Process {
+    pid: u64,
+
+    /// The time the process started in clock ticks since boot, or zero if it is unknown.
+    started: u64,
+
+    /// The inode of the pid namespace of the process, or zero if it is unknown.
+    namespace: u64,
+}
+
+impl Process {
+    fn current() -> Self {
+        let pid = std::process::id() as u64;
+
+        Self {
+            pid,
+            started: start_time("self").unwrap_or(0),
+            namespace: pid_namespace().unwrap_or(0),
+        }
+    }
+
+    /// Checks whether the process is still running, as seen from the process `current`.
+    ///
+    /// A process is only known to have exited if it is in the same pid namespace, and no process
+    /// with its pid is running or the one that is started at a different time. Where neither is
+    /// known, the pid alone is checked.
+    fn is_alive(&self, current: &Process) -> bool {
+        if self == current || self.namespace != current.namespace {
+            return true;
+        }
+
+        if self.started != 0 {
+            return start_time(&self.pid.to_string()) == Some(self.started);
+        }
+
+        let result = unsafe { libc::kill(self.pid as libc::pid_t, 0) };
+        result == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+    }
+}
+
+/// Reads the start time of a process from `/proc/<pid>/stat`.
+fn start_time(pid: &str) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+
+    // The start time is the 22nd field, counting from the pid and the name of the process, which
+    // is in parentheses and may itself contain spaces
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+fn pid_namespace() -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata("/proc/self/ns/pid")
+        .ok()
+        .map(|metadata| metadata.ino())
+}
+
+/// The start of the shared memory, followed by the slots of the ring.
+///
+/// Every field is zero in a newly sized file, which is a valid value for all of the atomics.
+#[repr(C)]
+struct Header {
+    magic: AtomicU64,
+    version: u64,
+    capacity: u64,
+    event_size: u64,
+    event_align: u64,
+    cursor: Padded<AtomicU64>,
+    publishers: [Padded<Attacher>; MAX_PUBLISHERS],
+    subscribers: [Padded<Attacher>; MAX_SUBSCRIBERS],
+}
+
+/// The offsets of the slots in the shared memory. Each slot is the sequence of its event,
+/// followed by the event itself.
+struct Layout {
+    ring_offset: usize,
+    data_offset: usize,
+    stride: usize,
+    len: usize,
+}
+
+impl Layout {
+    fn of<T>(capacity: u64) -> Self {
+        let align = std::cmp::max(align_of::<T>(), align_of::<AtomicU64>());
+        let data_offset = round_up(size_of::<AtomicU64>(), align_of::<T>());
+        let stride = round_up(data_offset + size_of::<T>(), align);
+        let ring_offset = round_up(size_of::<Header>(), align);
+
+        Self {
+            ring_offset,
+            data_offset,
+            stride,
+            len: ring_offset + stride * capacity as usize,
+        }
+    }
+}
+
+fn round_up(n: usize, align: usize) -> usize {
+    n.div_ceil(align) * align
+}
+
+struct Mapping {
+    _mmap: MmapMut,
+    base: *mut u8,
+    layout: Layout,
+    capacity: u64,
+}
+
+// The mapping is only accessed through atomics, and through event slots that the sequences hand
+// out to a single writer at a time.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn header(&self) -> &Header {
+        unsafe { &*(self.base as *const Header) }
+    }
+
+    fn slot(&self, sequence: u64) -> *mut u8 {
+        let idx = ((sequence - 1) & (self.capacity - 1)) as usize;
+        unsafe {
+            self.base
+                .add(self.layout.ring_offset + idx * self.layout.stride)
+        }
+    }
+
+    fn slot_sequence(&self, sequence: u64) -> &AtomicU64 {
+        unsafe { &*(self.slot(sequence) as *const AtomicU64) }
+    }
+
+    fn data<T>(&self, sequence: u64) -> *mut T {
+        unsafe { self.slot(sequence).add(self.layout.data_offset) as *mut T }
+    }
+
+    /// Finds the oldest sequence that an attached subscriber has not read yet.
+    fn minimum_sequence(&self, default: u64) -> u64 {
+        self.header()
+            .subscribers
+            .iter()
+            .filter(|subscriber| subscriber.0.pid.load(Ordering::Acquire) != 0)
+            .map(|subscriber| subscriber.0.sequence.load(Ordering::Acquire))
+            .fold(default, std::cmp::min)
+    }
+
+    /// Takes a free slot in `attachers` for `process`.
+    fn attach(
+        &self,
+        attachers: &[Padded<Attacher>],
+        process: Process,
+        sequence: impl Fn() -> u64,
+    ) -> Option<usize> {
+        let idx = attachers.iter().position(|attacher| {
+            attacher
+                .0
+                .pid
+                .compare_exchange(0, process.pid, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+        })?;
+
+        // A released slot is left at sequence zero, so publishers wait rather than overwrite
+        // events until the sequence is set
+        let attacher = &attachers[idx].0;
+        attacher.sequence.store(sequence(), Ordering::Release);
+        attacher.started.store(process.started, Ordering::Release);
+        attacher
+            .namespace
+            .store(process.namespace, Ordering::Release);
+
+        Some(idx)
+    }
+
+    fn detach(&self, attacher: &Attacher, pid: u64) {
+        // The slot may already have been reaped and taken by another attacher
+        if attacher.pid.load(Ordering::Acquire) == pid {
+            attacher.release(pid);
+        }
+    }
+
+    fn reap(&self) -> usize {
+        let current = Process::current();
+        let header = self.header();
+        let mut reaped = 0;
+
+        for publisher in header.publishers.iter().map(|padded| &padded.0) {
+            let pid = publisher.pid.load(Ordering::Acquire);
+            if pid == 0 || publisher.process(pid).is_alive(&current) {
+                continue;
+            }
+
+            // Skip the event the publisher claimed but never published, which subscribers would
+            // otherwise wait on forever
+            let pending = publisher.sequence.load(Ordering::Acquire);
+            if pending != 0 && self.claimed_by_crashed(publisher, pending) {
+                let slot_sequence = self.slot_sequence(pending);
+                if slot_sequence.load(Ordering::Acquire) & !SKIPPED < pending {
+                    slot_sequence.store(pending | SKIPPED, Ordering::Release);
+                }
+            }
+
+            if publisher.release(pid) {
+                reaped += 1;
+            }
+        }
+
+        for subscriber in header.subscribers.iter().map(|padded| &padded.0) {
+            let pid = subscriber.pid.load(Ordering::Acquire);
+            if pid == 0 || subscriber.process(pid).is_alive(&current) {
+                continue;
+            }
+
+            if subscriber.release(pid) {
+                reaped += 1;
+            }
+        }
+
+        reaped
+    }
+
+    /// Checks whether the crashed `publisher` claimed the `pending` sequence it had recorded.
+    ///
+    /// A publisher records the sequence before it claims it from the cursor, so the sequence may
+    /// not have been claimed at all, or been claimed by another publisher instead. That publisher
+    /// records it too, and keeps it recorded until it has published to it.
+    fn claimed_by_crashed(&self, publisher: &Attacher, pending: u64) -> bool {
+        let header = self.header();
+
+        header.cursor.0.load(Ordering::Acquire) >= pending
+            && !header
+                .publishers
+                .iter()
+                .map(|padded| &padded.0)
+                .filter(|other| !std::ptr::eq(*other, publisher))
+                .any(|other| other.sequence.load(Ordering::Acquire) == pending)
+    }
+}
+
+fn shm_error(err: std::io::Error) -> Error {
+    Error::SharedMemory(err.to_string())
+}
+
+/// Resolves the name of a shared ring to its backing file, which is in `/dev/shm` where it exists
+/// so that the ring is never written back to disk.
+fn shm_path(name: &str) -> Result<PathBuf, Error> {
+    if name.is_empty() || name.contains('/') {
+        return Err(Error::InvalidConfig(
+            "shared ring name must be non-empty and must not contain '/'",
+        ));
+    }
+
+    let dir = PathBuf::from("/dev/shm");
+    let dir = if dir.is_dir() {
+        dir
+    } else {
+        std::env::temp_dir()
+    };
+
+    Ok(dir.join(format!("eventador-{}", name)))
+}
+
+fn map(file: &File) -> Result<MmapMut, Error> {
+    unsafe { MmapMut::map_mut(file) }.map_err(shm_error)
+}
+
+/// A ring of events in shared memory, that publishers and subscribers in different processes on
+/// the same host attach to by name.
+///
+/// The ring, its cursor, and the sequences of every attached publisher and subscriber are laid
+/// out in a memory-mapped file, so events are copied in and out of the ring without any system
+/// calls. Events must therefore be plain-old-data, as checked by [`bytemuck::Pod`], and every
+/// process must use the same event type.
+///
+/// Publishers wait on the slowest subscriber, as with
+/// [`WaitStrategy::AllSubscribers`](crate::WaitStrategy::AllSubscribers). Waiting publishers and
+/// subscribers periodically check whether the process of any other attacher has exited without
+/// detaching, and clean up after it, so that a crashed process doesn't halt the ring.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// #[derive(Copy, Clone, Pod, Zeroable)]
+/// #[repr(C)]
+/// struct Tick {
+///     price: u64,
+///     quantity: u64,
+/// }
+///
+/// // In one process
+/// let ring = SharedEventador::<Tick>::create("ticks", 1024)?;
+/// let mut publisher = ring.publisher()?;
+/// publisher.send(Tick { price: 100, quantity: 5 });
+///
+/// // In another process
+/// let ring = SharedEventador::<Tick>::open("ticks")?;
+/// let mut subscriber = ring.subscribe()?;
+/// let tick = subscriber.recv()?;
+/// ```
+///
+pub struct SharedEventador<T> {
+    name: String,
+    mapping: Arc<Mapping>,
+    backoff: Backoff,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Pod> SharedEventador<T> {
+    /// Creates a shared ring that can hold `capacity` events, and attaches to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCapacity`] if `capacity` is not a power of two greater than one,
+    /// or [`Error::SharedMemory`] if a ring with the same name already exists or could not be
+    /// mapped.
+    ///
+    pub fn create(name: &str, capacity: u64) -> Result<Self, Error> {
+        if capacity < 2 || !capacity.is_power_of_two() {
+            return Err(Error::InvalidCapacity(capacity));
+        }
+
+        let path = shm_path(name)?;
+        let layout = Layout::of::<T>(capacity);
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(shm_error)?;
+        file.set_len(layout.len as u64).map_err(shm_error)?;
+
+        let mut mmap = map(&file)?;
+        let base = mmap.as_mut_ptr();
+
+        unsafe {
+            let header = base as *mut Header;
+            (*header).version = VERSION;
+            (*header).capacity = capacity;
+            (*header).event_size = size_of::<T>() as u64;
+            (*header).event_align = align_of::<T>() as u64;
+            (*header).magic.store(MAGIC, Ordering::Release);
+        }
+
+        Ok(Self::from_mapping(name, mmap, base, layout, capacity))
+    }
+
+    /// Attaches to a shared ring that was created by this or another process.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if the ring was created for a different event type or by
+    /// an incompatible version, or [`Error::SharedMemory`] if it doesn't exist or could not be
+    /// mapped.
+    ///
+    pub fn open(name: &str) -> Result<Self, Error> {
+        let path = shm_path(name)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .map_err(shm_error)?;
+
+        // The creator sizes the file before initializing the header, and publishes the magic
+        // number last
+        let started = Instant::now();
+        loop {
+            let len = file.metadata().map_err(shm_error)?.len() as usize;
+
+            if len >= size_of::<Header>() {
+                let mut mmap = map(&file)?;
+                let base = mmap.as_mut_ptr();
+                let header = unsafe { &*(base as *const Header) };
+
+                if header.magic.load(Ordering::Acquire) == MAGIC {
+                    if header.version != VERSION {
+                        return Err(Error::InvalidConfig(
+                            "shared ring was created by an incompatible version",
+                        ));
+                    }
+
+                    if header.event_size != size_of::<T>() as u64
+                        || header.event_align != align_of::<T>() as u64
+                    {
+                        return Err(Error::InvalidConfig(
+                            "event type does not match the shared ring",
+                        ));
+                    }
+
+                    let capacity = header.capacity;
+                    let layout = Layout::of::<T>(capacity);
+                    if mmap.len() < layout.len {
+                        return Err(Error::SharedMemory(String::from(
+                            "shared ring is smaller than its capacity",
+                        )));
+                    }
+
+                    return Ok(Self::from_mapping(name, mmap, base, layout, capacity));
+                }
+            }
+
+            if started.elapsed() > ATTACH_TIMEOUT {
+                return Err(Error::SharedMemory(String::from(
+                    "shared ring was not initialized by its creator",
+                )));
+            }
+
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Removes the shared ring with the given name, so that no more processes can attach to it.
+    ///
+    /// Processes that are already attached keep their mapping until they drop it.
+    pub fn remove(name: &str) -> Result<(), Error> {
+        std::fs::remove_file(shm_path(name)?).map_err(shm_error)
+    }
+
+    fn from_mapping(
+        name: &str,
+        mmap: MmapMut,
+        base: *mut u8,
+        layout: Layout,
+        capacity: u64,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            mapping: Arc::new(Mapping {
+                _mmap: mmap,
+                base,
+                layout,
+                capacity,
+            }),
+            backoff: Backoff::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets how publishers and subscribers created from this handle wait on each other.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Get the name the ring was created with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the number of events the ring can hold.
+    pub fn capacity(&self) -> u64 {
+        self.mapping.capacity
+    }
+
+    /// Get the sequence of the most recently claimed event.
+    pub fn cursor(&self) -> u64 {
+        self.mapping.header().cursor.0.load(Ordering::Acquire)
+    }
+
+    /// Attaches a publisher to the ring.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SharedMemory`] if the maximum number of publishers are already attached.
+    ///
+    pub fn publisher(&self) -> Result<SharedPublisher<T>, Error> {
+        self.attach_publisher(Process::current())
+    }
+
+    /// Attaches a subscriber to the ring, which receives every event published after it
+    /// attached.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SharedMemory`] if the maximum number of subscribers are already
+    /// attached.
+    ///
+    pub fn subscribe(&self) -> Result<SharedSubscriber<T>, Error> {
+        self.attach_subscriber(Process::current())
+    }
+
+    /// Cleans up after any publishers and subscribers whose processes exited without detaching,
+    /// and returns how many were cleaned up.
+    ///
+    /// Subscribers of a crashed process no longer hold back publishers, and an event that a
+    /// crashed publisher claimed but never published is skipped by subscribers. This is also done
+    /// periodically by waiting publishers and subscribers.
+    pub fn reap(&self) -> usize {
+        self.mapping.reap()
+    }
+
+    fn attach_publisher(&self, process: Process) -> Result<SharedPublisher<T>, Error> {
+        let idx = self
+            .mapping
+            .attach(&self.mapping.header().publishers, process, || 0)
+            .ok_or_else(|| Error::SharedMemory(String::from("too many publishers attached")))?;
+
+        Ok(SharedPublisher {
+            mapping: self.mapping.clone(),
+            idx,
+            pid: process.pid,
+            backoff: self.backoff,
+            _marker: PhantomData,
+        })
+    }
+
+    fn attach_subscriber(&self, process: Process) -> Result<SharedSubscriber<T>, Error> {
+        let cursor = &self.mapping.header().cursor.0;
+        let idx = self
+            .mapping
+            .attach(&self.mapping.header().subscribers, process, || {
+                cursor.load(Ordering::Acquire) + 1
+            })
+            .ok_or_else(|| Error::SharedMemory(String::from("too many subscribers attached")))?;
+
+        let sequence = self.mapping.header().subscribers[idx]
+            .0
+            .sequence
+            .load(Ordering::Acquire);
+
+        Ok(SharedSubscriber {
+            mapping: self.mapping.clone(),
+            idx,
+            pid: process.pid,
+            sequence,
+            backoff: self.backoff,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T> Clone for SharedEventador<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            mapping: self.mapping.clone(),
+            backoff: self.backoff,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A handle to publish events to a [`SharedEventador`].
+///
+/// Each publisher takes one of the ring's publisher slots until it is dropped.
+pub struct SharedPublisher<T> {
+    mapping: Arc<Mapping>,
+    idx: usize,
+    pid: u64,
+    backoff: Backoff,
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T: Pod> SharedPublisher<T> {
+    fn attacher(&self) -> &Attacher {
+        &self.mapping.header().publishers[self.idx].0
+    }
+
+    /// Claims the next sequence, unless its slot still holds an event that a subscriber has not
+    /// read.
+    ///
+    /// The sequence is recorded before it is claimed, so that a crash right after claiming it never
+    /// leaves a claim behind that other processes don't know about.
+    fn try_claim(&self) -> Option<u64> {
+        let cursor = &self.mapping.header().cursor.0;
+
+        loop {
+            let current = cursor.load(Ordering::Acquire);
+            let next = current + 1;
+
+            if next > self.mapping.capacity
+                && self.mapping.minimum_sequence(next) <= next - self.mapping.capacity
+            {
+                self.attacher().sequence.store(0, Ordering::Release);
+                return None;
+            }
+
+            self.attacher().sequence.store(next, Ordering::Release);
+            if cursor
+                .compare_exchange(current, next, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                return Some(next);
+            }
+        }
+    }
+
+    fn write(&self, sequence: u64, event: T) {
+        unsafe { self.mapping.data::<T>(sequence).write(event) };
+
+        self.mapping
+            .slot_sequence(sequence)
+            .store(sequence, Ordering::Release);
+        self.attacher().sequence.store(0, Ordering::Release);
+    }
+
+    /// Publish an event to the ring, waiting for the slowest subscriber if the ring is full.
+    pub fn send(&mut self, event: T) {
+        let mut last_reap = Instant::now();
+
+        let sequence = loop {
+            if let Some(sequence) = self.try_claim() {
+                break sequence;
+            }
+
+            if last_reap.elapsed() >= REAP_INTERVAL {
+                self.mapping.reap();
+                last_reap = Instant::now();
+            }
+
+            self.backoff.wait();
+        };
+
+        self.write(sequence, event);
+    }
+
+    /// Attempt to publish an event to the ring without waiting on subscribers.
+    ///
+    /// Returns the sequence of the published event, or hands the event back in
+    /// [`TrySendError::Full`] if the ring is full of unread events.
+    pub fn try_send(&mut self, event: T) -> Result<u64, TrySendError<T>> {
+        match self.try_claim() {
+            Some(sequence) => {
+                self.write(sequence, event);
+                Ok(sequence)
+            }
+            None => Err(TrySendError::Full(event)),
+        }
+    }
+}
+
+impl<T> Drop for SharedPublisher<T> {
+    fn drop(&mut self) {
+        let attacher = &self.mapping.header().publishers[self.idx].0;
+        self.mapping.detach(attacher, self.pid);
+    }
+}
+
+/// A handle to receive events from a [`SharedEventador`].
+///
+/// Each subscriber takes one of the ring's subscriber slots until it is dropped, and holds back
+/// publishers until it has read their events.
+pub struct SharedSubscriber<T> {
+    mapping: Arc<Mapping>,
+    idx: usize,
+    pid: u64,
+    sequence: u64,
+    backoff: Backoff,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Pod> SharedSubscriber<T> {
+    fn attacher(&self) -> &Attacher {
+        &self.mapping.header().subscribers[self.idx].0
+    }
+
+    /// Get the sequence of the next event the subscriber will read.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Receive the next event, waiting for it to be published.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if another process cleaned up after this subscriber, because
+    /// it could not see that this process is still running.
+    ///
+    pub fn recv(&mut self) -> Result<T, Error> {
+        let mut last_reap = Instant::now();
+
+        loop {
+            if let Some(event) = self.try_recv()? {
+                return Ok(event);
+            }
+
+            if last_reap.elapsed() >= REAP_INTERVAL {
+                self.mapping.reap();
+                last_reap = Instant::now();
+            }
+
+            self.backoff.wait();
+        }
+    }
+
+    /// Receive the next event if it has already been published.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if another process cleaned up after this subscriber.
+    ///
+    pub fn try_recv(&mut self) -> Result<Option<T>, Error> {
+        loop {
+            if self.attacher().pid.load(Ordering::Acquire) != self.pid {
+                return Err(Error::Evicted);
+            }
+
+            let published = self
+                .mapping
+                .slot_sequence(self.sequence)
+                .load(Ordering::Acquire);
+
+            if published == self.sequence | SKIPPED {
+                self.advance();
+                continue;
+            }
+
+            if published != self.sequence {
+                return Ok(None);
+            }
+
+            // Publishers can't overwrite the slot until this subscriber has moved past it
+            let event = unsafe { self.mapping.data::<T>(self.sequence).read() };
+            self.advance();
+
+            return Ok(Some(event));
+        }
+    }
+
+    fn advance(&mut self) {
+        self.sequence += 1;
+        self.attacher()
+            .sequence
+            .store(self.sequence, Ordering::Release);
+    }
+}
+
+impl<T> Drop for SharedSubscriber<T> {
+    fn drop(&mut self) {
+        let attacher = &self.mapping.header().subscribers[self.idx].0;
+        self.mapping.detach(attacher, self.pid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Process;
+    use crate::{Error, SharedEventador};
+    use std::sync::atomic::Ordering;
+
+    fn ring_name(test: &str) -> String {
+        format!("test-{}-{}", test, std::process::id())
+    }
+
+    /// Returns a process that has exited, and whose pid was reused by this process.
+    fn crashed_process() -> Process {
+        let current = Process::current();
+        assert_ne!(0, current.started);
+
+        Process {
+            started: current.started - 1,
+            ..current
+        }
+    }
+
+    #[test]
+    fn publishes_across_attachments() {
+        let name = ring_name("attach");
+        let created = SharedEventador::<[u64; 2]>::create(&name, 4).unwrap();
+
+        // A separate mapping of the same ring, as another process would have
+        let opened = SharedEventador::<[u64; 2]>::open(&name).unwrap();
+        let mut subscriber = opened.subscribe().unwrap();
+
+        let publish_thread = std::thread::spawn(move || {
+            let mut publisher = created.publisher().unwrap();
+            for i in 1..=32 {
+                publisher.send([i, i * 2]);
+            }
+        });
+
+        for i in 1..=32 {
+            assert_eq!([i, i * 2], subscriber.recv().unwrap());
+        }
+
+        publish_thread.join().unwrap();
+        assert_eq!(32, opened.cursor());
+
+        assert_eq!(
+            Some(Error::InvalidConfig(
+                "event type does not match the shared ring"
+            )),
+            SharedEventador::<u8>::open(&name).err()
+        );
+        assert!(SharedEventador::<[u64; 2]>::create(&name, 4).is_err());
+
+        SharedEventador::<[u64; 2]>::remove(&name).unwrap();
+    }
+
+    #[test]
+    fn reaps_crashed_attachers() {
+        let name = ring_name("reap");
+        let ring = SharedEventador::<u64>::create(&name, 2).unwrap();
+        let crashed = crashed_process();
+
+        let mut subscriber = ring.subscribe().unwrap();
+        let _crashed_subscriber = ring.attach_subscriber(crashed).unwrap();
+
+        // A publisher that crashed after claiming a sequence, but before publishing to it
+        let crashed_publisher = ring.attach_publisher(crashed).unwrap();
+        assert_eq!(Some(1), crashed_publisher.try_claim());
+        std::mem::forget(crashed_publisher);
+
+        let mut publisher = ring.publisher().unwrap();
+        assert_eq!(Ok(2), publisher.try_send(2).map_err(|_| ()));
+        assert!(publisher.try_send(3).is_err());
+
+        assert_eq!(2, ring.reap());
+
+        // The unpublished event is skipped, and the crashed subscriber no longer gates publishers
+        assert_eq!(2, subscriber.recv().unwrap());
+        assert_eq!(Ok(3), publisher.try_send(3).map_err(|_| ()));
+        assert_eq!(3, subscriber.recv().unwrap());
+
+        SharedEventador::<u64>::remove(&name).unwrap();
+    }
+
+    #[test]
+    fn reaps_publisher_that_crashed_before_claiming() {
+        let name = ring_name("reap-unclaimed");
+        let ring = SharedEventador::<u64>::create(&name, 2).unwrap();
+        let mut subscriber = ring.subscribe().unwrap();
+
+        // A publisher that crashed after recording the next sequence, but before claiming it
+        let crashed_publisher = ring.attach_publisher(crashed_process()).unwrap();
+        crashed_publisher
+            .attacher()
+            .sequence
+            .store(1, Ordering::Release);
+        std::mem::forget(crashed_publisher);
+
+        assert_eq!(1, ring.reap());
+
+        // The sequence is still claimed and published by the next publisher
+        let mut publisher = ring.publisher().unwrap();
+        assert_eq!(Ok(1), publisher.try_send(1).map_err(|_| ()));
+        assert_eq!(1, subscriber.recv().unwrap());
+
+        SharedEventador::<u64>::remove(&name).unwrap();
+    }
+
+    #[test]
+    fn does_not_reap_other_namespaces() {
+        let name = ring_name("reap-namespace");
+        let ring = SharedEventador::<u64>::create(&name, 2).unwrap();
+
+        // A pid in another namespace may belong to any process in this one
+        let _subscriber = ring
+            .attach_subscriber(Process {
+                namespace: Process::current().namespace + 1,
+                ..crashed_process()
+            })
+            .unwrap();
+        let _own_subscriber = ring.subscribe().unwrap();
+
+        assert_eq!(0, ring.reap());
+
+        SharedEventador::<u64>::remove(&name).unwrap();
+    }
+}
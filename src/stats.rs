@@ -0,0 +1,277 @@
+use crate::latency::LatencyHistogram;
+use crossbeam::utils::CachePadded;
+use lockfree::map::{Map, Preview};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// A snapshot of the state of an event-bus, returned by [`Eventador::stats`](crate::Eventador::stats).
+///
+/// Each value is read independently while publishers and subscribers keep running, so the snapshot
+/// is not guaranteed to be consistent across values.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(4)?;
+/// let subscriber = eventbus.subscribe::<usize>();
+///
+/// eventbus.publish(1_usize);
+///
+/// let stats = eventbus.stats();
+/// assert_eq!(1, stats.published_count::<usize>());
+/// assert_eq!(1, stats.occupancy);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Stats {
+    /// The name of the event-bus, if it was given one.
+    pub name: Option<String>,
+
+    /// The number of events the ring can hold.
+    pub capacity: u64,
+
+    /// The sequence of the most recently claimed event.
+    pub cursor: u64,
+
+    /// The sequence of the oldest event that a subscriber has not read yet, or the next sequence
+    /// to be published if there are no subscribers.
+    pub min_gating_sequence: u64,
+
+    /// The number of events in the ring that have not been read by all subscribers.
+    pub occupancy: u64,
+
+    /// The number of subscribers that gate publishers.
+    pub subscriber_count: usize,
+
+    /// The position and lag of each subscriber.
+    pub subscribers: Vec<SubscriberStats>,
+
+    /// The number of events published per type.
+    pub published: HashMap<TypeId, PublishedCount>,
+
+    /// The number of events that publishers overwrote in the ring before every subscriber read
+    /// them. Each such event is counted once, however many subscribers had not read it.
+    pub overwrites: u64,
+
+    /// The number of events that were overwritten before a subscriber read them, counted once for
+    /// each subscriber that skipped over them.
+    ///
+    /// A lagging subscriber skips ahead to the event that overwrote the one it was waiting on, so
+    /// it can also skip events that were still held in the ring. Those are not counted, so with a
+    /// single subscriber that catches up, `lag_drops` equals `overwrites`.
+    pub lag_drops: u64,
+
+    /// The number of events that [`Handler`](crate::Handler)s could not handle, and published as
+    /// [`DeadLetter`](crate::DeadLetter)s.
+    pub dead_letters: u64,
+
+    /// The total time publishers spent waiting on lagging subscribers.
+    pub publisher_wait: Duration,
+
+    /// The publish-to-read latencies of all current subscribers, if latency tracking was enabled
+    /// through [`EventadorBuilder::track_latency`](crate::EventadorBuilder::track_latency).
+    pub latency: Option<LatencyHistogram>,
+}
+
+impl Stats {
+    /// Get the number of published events of type `T`.
+    pub fn published_count<T: 'static>(&self) -> u64 {
+        self.published
+            .get(&TypeId::of::<T>())
+            .map_or(0, |published| published.count)
+    }
+}
+
+/// The position of a subscriber in the ring.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SubscriberStats {
+    /// The id of the subscriber.
+    pub id: u64,
+
+    /// The sequence of the next event the subscriber will read.
+    pub sequence: u64,
+
+    /// The number of published events the subscriber has not read yet.
+    pub lag: u64,
+}
+
+/// The number of published events of a single type.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PublishedCount {
+    /// The name of the type, as given by [`std::any::type_name`].
+    pub type_name: &'static str,
+
+    /// The number of events published.
+    pub count: u64,
+}
+
+struct TypeCounter {
+    type_name: &'static str,
+    count: CachePadded<AtomicU64>,
+}
+
+/// The counters behind [`Stats`], each padded to its own cache-line so that publishers and
+/// subscribers recording different counters do not contend with each other.
+pub(crate) struct Counters {
+    published: Map<TypeId, TypeCounter>,
+    overwrites: CachePadded<AtomicU64>,
+    lag_drops: CachePadded<AtomicU64>,
+    dead_letters: CachePadded<AtomicU64>,
+    publisher_wait_nanos: CachePadded<AtomicU64>,
+}
+
+impl Counters {
+    pub fn new() -> Self {
+        Self {
+            published: Map::new(),
+            overwrites: CachePadded::new(AtomicU64::new(0)),
+            lag_drops: CachePadded::new(AtomicU64::new(0)),
+            dead_letters: CachePadded::new(AtomicU64::new(0)),
+            publisher_wait_nanos: CachePadded::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn record_publish(&self, type_id: TypeId, type_name: &'static str) {
+        if let Some(entry) = self.published.get(&type_id) {
+            entry.val().count.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        // Only the first publisher of a type inserts its counter, concurrent ones count into it
+        self.published
+            .insert_with(type_id, |_, _, stored| match stored {
+                Some(_) => Preview::Discard,
+                None => Preview::New(TypeCounter {
+                    type_name,
+                    count: CachePadded::new(AtomicU64::new(0)),
+                }),
+            });
+
+        if let Some(entry) = self.published.get(&type_id) {
+            entry.val().count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_overwrites(&self, n: u64) {
+        self.overwrites.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn record_lag_drops(&self, n: u64) {
+        self.lag_drops.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn record_dead_letter(&self) {
+        self.dead_letters.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_publisher_wait(&self, waited: Duration) {
+        self.publisher_wait_nanos
+            .fetch_add(waited.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn published(&self) -> HashMap<TypeId, PublishedCount> {
+        self.published
+            .iter()
+            .map(|entry| {
+                let counter = entry.val();
+
+                (
+                    *entry.key(),
+                    PublishedCount {
+                        type_name: counter.type_name,
+                        count: counter.count.load(Ordering::Relaxed),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn overwrites(&self) -> u64 {
+        self.overwrites.load(Ordering::Relaxed)
+    }
+
+    pub fn lag_drops(&self) -> u64 {
+        self.lag_drops.load(Ordering::Relaxed)
+    }
+
+    pub fn dead_letters(&self) -> u64 {
+        self.dead_letters.load(Ordering::Relaxed)
+    }
+
+    pub fn publisher_wait(&self) -> Duration {
+        Duration::from_nanos(self.publisher_wait_nanos.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Eventador, WaitStrategy};
+
+    #[test]
+    fn tracks_occupancy_and_lag() {
+        let eventbus = Eventador::new(4).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+        let lagging = eventbus.subscribe::<usize>();
+
+        for i in 1..=3_usize {
+            eventbus.publish(i);
+        }
+        eventbus.publish("other");
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+
+        let stats = eventbus.stats();
+        assert_eq!(4, stats.cursor);
+        assert_eq!(1, stats.min_gating_sequence);
+        assert_eq!(4, stats.occupancy);
+        assert_eq!(2, stats.subscriber_count);
+        assert_eq!(3, stats.published_count::<usize>());
+        assert_eq!(1, stats.published_count::<&str>());
+
+        let mut lags: Vec<(u64, u64)> = stats.subscribers.iter().map(|s| (s.id, s.lag)).collect();
+        lags.sort_unstable();
+        assert_eq!(vec![(subscriber.id(), 3), (lagging.id(), 4)], lags);
+    }
+
+    #[test]
+    fn counts_overwrites_and_lag_drops() {
+        let eventbus = Eventador::with_strategy(4, WaitStrategy::NoWait).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        for i in 1..=6_usize {
+            eventbus.publish(i);
+        }
+
+        // The subscriber skips ahead to the event that overwrote the one it was waiting on, past
+        // events 1 and 2 that were overwritten and events 3 and 4 that were still held
+        assert_eq!(5, *subscriber.recv().unwrap());
+
+        let stats = eventbus.stats();
+        assert_eq!(2, stats.overwrites);
+        assert_eq!(2, stats.lag_drops);
+        assert_eq!(1, stats.occupancy);
+    }
+
+    #[test]
+    fn records_publisher_wait() {
+        let eventbus = Eventador::new(2).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        let publish_bus = eventbus.clone();
+        let publish_thread = std::thread::spawn(move || {
+            for i in 1..=3_usize {
+                publish_bus.publish(i);
+            }
+        });
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert_eq!(1, *subscriber.recv().unwrap());
+        publish_thread.join().unwrap();
+
+        assert!(eventbus.stats().publisher_wait >= std::time::Duration::from_millis(40));
+    }
+}
@@ -1,11 +1,14 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crossbeam::sync::Parker;
-
+use crate::error::Error;
 use crate::event::EventRead;
+use crate::latency::{self, LatencyHistogram, LatencyRecorder};
 use crate::ring_buffer::{EventWrapper, RingBuffer};
-use crate::sequence::Sequence;
-use crate::WaitStrategy;
+use crate::sequence::gating_sequence::GatingSequence;
+use crate::throttle::{DebouncedSubscriber, ThrottledSubscriber};
+use crate::waiter::Alerter;
+use crate::window::{Window, WindowedSubscriber};
 
 /// A handle to receive events that were subscribed to from the event-bus.
 ///
@@ -27,13 +30,13 @@ use crate::WaitStrategy;
 /// let mut i: usize = 1234;
 /// eventbus.publish(i);
 ///
-/// let mut msg = subscriber.recv();
+/// let mut msg = subscriber.recv()?;
 /// assert_eq!(i, *msg);
 /// ```
 ///
 pub struct Subscriber<T> {
     ring: Arc<RingBuffer>,
-    sequence: Arc<Sequence>,
+    sequence: Arc<GatingSequence>,
     _marker: std::marker::PhantomData<T>,
 }
 
@@ -41,7 +44,7 @@ impl<T: 'static> Subscriber<T>
 where
     T: Send,
 {
-    pub(crate) fn new(ring: Arc<RingBuffer>, sequence: Arc<Sequence>) -> Self {
+    pub(crate) fn new(ring: Arc<RingBuffer>, sequence: Arc<GatingSequence>) -> Self {
         Self {
             ring,
             sequence,
@@ -57,12 +60,110 @@ where
         self.sequence.get()
     }
 
-    pub(crate) fn read_event<'b>(&self, envelope: EventWrapper) -> Option<EventRead<'b, T>> {
-        let event_opt: Option<EventRead<T>> = unsafe { envelope.read() };
-        envelope.stop_waiting();
+    /// Get the id that identifies the [`Subscriber`] on the event-bus, such as in an
+    /// [`Eviction`](crate::Eviction).
+    pub fn id(&self) -> u64 {
+        self.sequence.id()
+    }
+
+    /// Get a snapshot of how long the events read by the [`Subscriber`] spent in the ring after
+    /// being published.
+    ///
+    /// Returns `None` unless latency tracking was enabled through
+    /// [`EventadorBuilder::track_latency`](crate::EventadorBuilder::track_latency).
+    pub fn latency_histogram(&self) -> Option<LatencyHistogram> {
+        self.sequence.latency().map(LatencyRecorder::snapshot)
+    }
+
+    /// Get the number of events the [`Subscriber`] skipped over because they were overwritten.
+    pub(crate) fn lagged(&self) -> u64 {
+        self.sequence.lagged()
+    }
+
+    /// Get when the event the [`Subscriber`] last received was published, if it was timestamped.
+    pub(crate) fn published_at(&self) -> Option<Instant> {
+        match self.sequence.received_published_at() {
+            0 => None,
+            published_at => Some(latency::to_instant(published_at)),
+        }
+    }
+
+    /// Groups the events that the [`Subscriber`] receives into windows.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut subscriber = eventbus.subscribe::<usize>().window(Window::tumbling_count(2));
+    ///
+    /// eventbus.publish(1_usize);
+    /// eventbus.publish(2_usize);
+    ///
+    /// let sum = subscriber.recv_fold(0, |sum, i| sum + i)?;
+    /// assert_eq!(3, sum);
+    /// ```
+    ///
+    pub fn window(self, window: Window) -> WindowedSubscriber<T> {
+        self.ring.timestamp_events();
+        WindowedSubscriber::new(self, window)
+    }
+
+    /// Only receives the last event of each burst, once no other event has followed it for `delay`.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut subscriber = eventbus.subscribe::<usize>().debounce(Duration::from_millis(50));
+    ///
+    /// eventbus.publish(1_usize);
+    /// eventbus.publish(2_usize);
+    ///
+    /// assert_eq!(2, *subscriber.recv()?);
+    /// ```
+    ///
+    pub fn debounce(self, delay: Duration) -> DebouncedSubscriber<T> {
+        DebouncedSubscriber::new(self, delay)
+    }
+
+    /// Receives at most one event per `interval`, skipping the events in between.
+    ///
+    /// The interval is the inverse of a rate, so `Duration::from_secs(1) / 10` receives at most
+    /// ten events per second. It is taken as an interval so that rates below one event per second,
+    /// such as one per minute, are exact.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let mut subscriber = eventbus.subscribe::<usize>().throttle(Duration::from_secs(1));
+    ///
+    /// eventbus.publish(1_usize);
+    /// eventbus.publish(2_usize);
+    ///
+    /// assert_eq!(1, *subscriber.recv()?);
+    /// ```
+    ///
+    pub fn throttle(self, interval: Duration) -> ThrottledSubscriber<T> {
+        ThrottledSubscriber::new(self, interval)
+    }
+
+    pub(crate) fn read_event(&self, envelope: EventWrapper) -> Option<EventRead<T>> {
+        let event_opt: Option<EventRead<T>> = envelope.read();
+
+        if event_opt.is_some() {
+            self.ring
+                .record_receive(&self.sequence, self.sequence.get(), &envelope);
+        }
 
         self.sequence.increment();
-        return event_opt;
+        event_opt
     }
 
     /// Synchronously read an event of the correct type from the event-bus.
@@ -78,12 +179,33 @@ where
     /// let mut i: usize = 1234;
     /// eventbus.publish(i);
     ///
-    /// let mut msg = subscriber.recv();
+    /// let mut msg = subscriber.recv()?;
     /// assert_eq!(i, *msg);
     /// ```
     ///
-    pub fn recv<'b>(&self) -> EventRead<'b, T> {
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the Subscriber was evicted from the event-bus for lagging.
+    ///
+    pub fn recv(&self) -> Result<EventRead<T>, Error> {
+        loop {
+            if let Some(event) = self.recv_until(None)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Reads an event of the correct type from the event-bus, or returns `None` once the deadline
+    /// has passed without one.
+    pub(crate) fn recv_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<EventRead<T>>, Error> {
         loop {
+            if self.sequence.is_evicted() {
+                return Err(Error::Evicted);
+            }
+
             let sequence = self.sequence.get();
 
             let envelope = self
@@ -92,32 +214,275 @@ where
                 .expect("ring buffer was not pre-populated with empty event envelopes")
                 .clone();
 
-            envelope.start_waiting();
-
             let envelope_sequence = envelope.sequence();
             if sequence == envelope_sequence {
                 if let Some(event) = self.read_event(envelope) {
-                    return event;
+                    return Ok(Some(event));
                 }
             } else if sequence > envelope_sequence {
-                let parker = Parker::new();
-                envelope.add_subscriber(Box::new(parker.unparker().clone()));
-
-                parker.park();
+                let timeout = match deadline {
+                    Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                        Some(timeout) if !timeout.is_zero() => Some(timeout),
+                        _ => return Ok(None),
+                    },
+                    None => None,
+                };
 
-                if let Some(event) = self.read_event(envelope) {
-                    return event;
-                }
+                let alerter = Alerter::Thread(std::thread::current());
+                self.sequence
+                    .waiter()
+                    .wait(&envelope, sequence, alerter, || match timeout {
+                        Some(timeout) => std::thread::park_timeout(timeout),
+                        None => std::thread::park(),
+                    });
             } else {
-                // Publisher has overwritten an event that has not been read yet
-                match self.ring.wait_strategy() {
-                    WaitStrategy::AllSubscribers => unreachable!(),
+                // Publisher has overwritten an event that has not been read yet, which can happen
+                // with lossy wait-strategies or after rewinding to an event that was being wrapped
+                self.sequence.set(envelope_sequence);
 
-                    _ => {
-                        self.sequence.set(envelope_sequence);
-                    }
-                }
+                let overwritten =
+                    self.ring
+                        .record_overwritten(self.id(), sequence, envelope_sequence);
+                self.sequence.add_lagged(overwritten);
+            }
+        }
+    }
+
+    /// Moves the [`Subscriber`] to a specific sequence in the event-bus.
+    ///
+    /// The sequence must still be held in the ring, or be the next sequence to be published.
+    /// Publishers gate on the new position immediately, so moving forward frees up any slots they
+    /// were waiting on.
+    ///
+    /// Moving back is not atomic with concurrent publishers: one that checked the gating sequences
+    /// just before may still overwrite the events moved back to, which are then skipped over and
+    /// counted as lag. Every way of moving a subscriber back shares this.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let subscriber = eventbus.subscribe::<usize>();
+    ///
+    /// eventbus.publish(1_usize);
+    /// eventbus.publish(2_usize);
+    ///
+    /// subscriber.seek(2)?;
+    /// assert_eq!(2, *subscriber.recv()?);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Lagged`] if the event at the sequence has already been overwritten, or
+    /// [`Error::InvalidSequence`] if the sequence has not been published yet or is zero.
+    ///
+    pub fn seek(&self, sequence: u64) -> Result<(), Error> {
+        self.ring.seek(&self.sequence, sequence)
+    }
+
+    /// Skips any unread events so that the next read is of the most recently published event.
+    ///
+    /// If the latest event is not of the subscribed type, the [`Subscriber`] will instead receive
+    /// the next event of that type to be published. Returns the new sequence of the Subscriber.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let subscriber = eventbus.subscribe::<usize>();
+    ///
+    /// for i in 0..3_usize {
+    ///     eventbus.publish(i);
+    /// }
+    ///
+    /// subscriber.skip_to_latest();
+    /// assert_eq!(2, *subscriber.recv()?);
+    /// ```
+    ///
+    pub fn skip_to_latest(&self) -> u64 {
+        self.ring.skip_to_latest(&self.sequence)
+    }
+
+    /// Moves the [`Subscriber`] back by `n` sequences to re-read events that are still held in the
+    /// ring. Returns the new sequence of the Subscriber.
+    ///
+    /// Moving back races concurrent publishers as described on [`Subscriber::seek`].
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```ignore
+    /// let eventbus = Eventador::new(4)?;
+    /// let subscriber = eventbus.subscribe::<usize>();
+    ///
+    /// eventbus.publish(1234_usize);
+    /// assert_eq!(1234, *subscriber.recv()?);
+    ///
+    /// subscriber.rewind(1)?;
+    /// assert_eq!(1234, *subscriber.recv()?);
+    /// ```
+    ///
+    pub fn rewind(&self, n: u64) -> Result<u64, Error> {
+        self.ring.rewind(&self.sequence, n)
+    }
+}
+
+impl<T> Drop for Subscriber<T> {
+    fn drop(&mut self) {
+        self.ring
+            .sequencer()
+            .deregister_gating_sequence(&self.sequence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Eventador, WaitStrategy};
+
+    #[test]
+    fn seek_within_window() {
+        let eventbus = Eventador::new(4).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        for i in 1..=3_usize {
+            eventbus.publish(i);
+        }
+
+        assert_eq!(Err(Error::InvalidSequence(5)), subscriber.seek(5));
+        assert_eq!(Err(Error::InvalidSequence(0)), subscriber.seek(0));
+
+        assert!(subscriber.seek(3).is_ok());
+        assert_eq!(3, *subscriber.recv().unwrap());
+        assert_eq!(4, subscriber.sequence());
+    }
+
+    #[test]
+    fn seek_past_overwritten_events() {
+        let eventbus = Eventador::with_strategy(2, WaitStrategy::NoWait).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        for i in 1..=5_usize {
+            eventbus.publish(i);
+        }
+
+        assert_eq!(Err(Error::Lagged(3)), subscriber.seek(3));
+        assert!(subscriber.seek(4).is_ok());
+        assert_eq!(4, *subscriber.recv().unwrap());
+    }
+
+    #[test]
+    fn skip_to_latest_unblocks_publishers() {
+        let eventbus = Eventador::new(2).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        let publish_bus = eventbus.clone();
+        let publish_thread = std::thread::spawn(move || {
+            for i in 1..=4_usize {
+                publish_bus.publish(i);
             }
+        });
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert_eq!(2, subscriber.skip_to_latest());
+        assert_eq!(2, *subscriber.recv().unwrap());
+
+        publish_thread.join().unwrap();
+        assert_eq!(4, subscriber.skip_to_latest());
+        assert_eq!(4, *subscriber.recv().unwrap());
+        assert_eq!(5, subscriber.skip_to_latest());
+    }
+
+    #[test]
+    fn rewind_rereads_events() {
+        let eventbus = Eventador::new(4).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        eventbus.publish(1_usize);
+        eventbus.publish(2_usize);
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert_eq!(2, *subscriber.recv().unwrap());
+
+        assert_eq!(1, subscriber.rewind(2).unwrap());
+        assert_eq!(1, *subscriber.recv().unwrap());
+
+        // Rewinding past the first sequence isn't lag, since nothing was overwritten
+        assert_eq!(Err(Error::InvalidSequence(0)), subscriber.rewind(5));
+    }
+
+    #[test]
+    fn rewind_past_overwritten_events() {
+        let eventbus = Eventador::with_strategy(2, WaitStrategy::NoWait).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        for i in 1..=5_usize {
+            eventbus.publish(i);
+        }
+        assert!(subscriber.seek(5).is_ok());
+
+        // The sequence that was overwritten is reported, rather than zero
+        assert_eq!(Err(Error::Lagged(3)), subscriber.rewind(2));
+        assert_eq!(4, subscriber.rewind(1).unwrap());
+    }
+
+    #[test]
+    fn records_latency_histogram() {
+        let eventbus = Eventador::builder().track_latency(true).build().unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+        let other = eventbus.subscribe::<usize>();
+
+        eventbus.publish(1_usize);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert_eq!(1, *other.recv().unwrap());
+
+        let histogram = subscriber.latency_histogram().unwrap();
+        assert_eq!(1, histogram.count());
+        assert!(histogram.min() >= std::time::Duration::from_millis(10));
+
+        let aggregated = eventbus.stats().latency.unwrap();
+        assert_eq!(2, aggregated.count());
+    }
+
+    #[test]
+    fn latency_histogram_disabled() {
+        let eventbus = Eventador::builder().track_latency(false).build().unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+
+        eventbus.publish(1_usize);
+        assert_eq!(1, *subscriber.recv().unwrap());
+
+        assert!(subscriber.latency_histogram().is_none());
+        assert!(eventbus.stats().latency.is_none());
+    }
+
+    #[test]
+    #[ntest::timeout(5000)]
+    fn shared_between_threads() {
+        let eventbus = Eventador::new(4).unwrap();
+        let subscriber = std::sync::Arc::new(eventbus.subscribe::<usize>());
+
+        // A thread that stops waiting must not remove the registration of the other
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let subscriber = subscriber.clone();
+                std::thread::spawn(move || *subscriber.recv().unwrap())
+            })
+            .collect();
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        eventbus.publish(1_usize);
+        eventbus.publish(2_usize);
+
+        for reader in readers {
+            reader.join().unwrap();
         }
+        assert_eq!(3, subscriber.sequence());
     }
 }
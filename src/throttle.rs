@@ -0,0 +1,188 @@
+use crate::error::Error;
+use crate::event::EventRead;
+use crate::subscriber::Subscriber;
+use std::time::{Duration, Instant};
+
+/// A [`Subscriber`] that only receives the last of a burst of events, created by
+/// [`Subscriber::debounce`].
+///
+/// An event is received once no other event has followed it for the debounce delay, and the events
+/// it superseded are skipped. Events are only read off the ring while [`recv`](Self::recv) is
+/// waiting, so between calls the subscriber holds back publishers like any other.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(1024)?;
+/// let mut subscriber = eventbus
+///     .subscribe::<SearchQuery>()
+///     .debounce(Duration::from_millis(300));
+///
+/// let query = subscriber.recv()?;
+/// ```
+///
+pub struct DebouncedSubscriber<T> {
+    subscriber: Subscriber<T>,
+    delay: Duration,
+    pending: Option<(Instant, EventRead<T>)>,
+}
+
+impl<T: 'static + Send> DebouncedSubscriber<T> {
+    pub(crate) fn new(subscriber: Subscriber<T>, delay: Duration) -> Self {
+        Self {
+            subscriber,
+            delay,
+            pending: None,
+        }
+    }
+
+    /// Synchronously read the next event that was not followed by another one within the delay.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the subscriber was evicted from the event-bus for lagging.
+    ///
+    pub fn recv(&mut self) -> Result<EventRead<T>, Error> {
+        loop {
+            let deadline = self.pending.as_ref().map(|(at, _)| *at + self.delay);
+
+            match self.subscriber.recv_until(deadline)? {
+                Some(event) => self.pending = Some((Instant::now(), event)),
+                None => {
+                    if let Some((_, event)) = self.pending.take() {
+                        return Ok(event);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get the [`Subscriber`] that the events are received from.
+    pub fn subscriber(&self) -> &Subscriber<T> {
+        &self.subscriber
+    }
+}
+
+/// A [`Subscriber`] that receives at most one event per interval, created by
+/// [`Subscriber::throttle`].
+///
+/// The first event is received straight away, and the events that follow it within the interval
+/// are skipped. Events are only read off the ring while [`recv`](Self::recv) is waiting, so
+/// between calls the subscriber holds back publishers like any other. The events that are waiting
+/// when `recv` is called within the interval are skipped over at once.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(1024)?;
+/// let mut subscriber = eventbus
+///     .subscribe::<Alert>()
+///     .throttle(Duration::from_secs(60));
+///
+/// let alert = subscriber.recv()?;
+/// ```
+///
+pub struct ThrottledSubscriber<T> {
+    subscriber: Subscriber<T>,
+    interval: Duration,
+    next: Option<Instant>,
+}
+
+impl<T: 'static + Send> ThrottledSubscriber<T> {
+    pub(crate) fn new(subscriber: Subscriber<T>, interval: Duration) -> Self {
+        Self {
+            subscriber,
+            interval,
+            next: None,
+        }
+    }
+
+    /// Synchronously read the next event that was received at least an interval after the last
+    /// one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the subscriber was evicted from the event-bus for lagging.
+    ///
+    pub fn recv(&mut self) -> Result<EventRead<T>, Error> {
+        loop {
+            // Every event that is waiting within the interval would be skipped anyway
+            if self.next.is_some_and(|next| Instant::now() < next) {
+                self.subscriber.skip_to_latest();
+            }
+
+            let event = self.subscriber.recv()?;
+            let now = Instant::now();
+
+            if self.next.is_none_or(|next| now >= next) {
+                self.next = Some(now + self.interval);
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Get the [`Subscriber`] that the events are received from.
+    pub fn subscriber(&self) -> &Subscriber<T> {
+        &self.subscriber
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Eventador;
+    use std::time::Duration;
+
+    #[test]
+    fn debounces_bursts() {
+        let eventbus = Eventador::new(16).unwrap();
+        let mut subscriber = eventbus
+            .subscribe::<usize>()
+            .debounce(Duration::from_millis(30));
+
+        let publish_bus = eventbus.clone();
+        let publish_thread = std::thread::spawn(move || {
+            for i in 1..=3_usize {
+                publish_bus.publish(i);
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+            publish_bus.publish(4_usize);
+        });
+
+        assert_eq!(3, *subscriber.recv().unwrap());
+        assert_eq!(4, *subscriber.recv().unwrap());
+
+        publish_thread.join().unwrap();
+
+        // The skipped events were read off the ring
+        assert_eq!(5, subscriber.subscriber().sequence());
+    }
+
+    #[test]
+    fn throttles_events() {
+        let eventbus = Eventador::new(4).unwrap();
+        let mut subscriber = eventbus
+            .subscribe::<usize>()
+            .throttle(Duration::from_millis(50));
+
+        let publish_bus = eventbus.clone();
+        let publish_thread = std::thread::spawn(move || {
+            // More events than the ring holds, so skipped events must not gate publishers
+            for i in 1..=10_usize {
+                publish_bus.publish(i);
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+            publish_bus.publish(11_usize);
+        });
+
+        assert_eq!(1, *subscriber.recv().unwrap());
+        assert_eq!(11, *subscriber.recv().unwrap());
+
+        publish_thread.join().unwrap();
+    }
+}
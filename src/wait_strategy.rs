@@ -14,6 +14,36 @@ pub enum WaitStrategy {
     WaitForDuration(Duration),
 }
 
+/// Declares how a publisher waits while the ring is full of events that subscribers have not read.
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Backoff {
+    /// Busy-spin, for the lowest latency at the cost of a fully used core.
+    Spin,
+
+    /// Yield the thread, or task, to the scheduler between attempts.
+    Yield,
+
+    /// *Default*: Sleep for the given duration between attempts.
+    Sleep(Duration),
+}
+
+impl Backoff {
+    pub(crate) fn wait(&self) {
+        match self {
+            Backoff::Spin => std::hint::spin_loop(),
+            Backoff::Yield => std::thread::yield_now(),
+            Backoff::Sleep(duration) => std::thread::sleep(*duration),
+        }
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::Sleep(Duration::from_micros(100))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Eventador, WaitStrategy};
@@ -33,7 +63,7 @@ mod tests {
 
         std::thread::sleep(std::time::Duration::from_secs(1));
         let i: usize = 0;
-        let msg = subscriber.recv();
+        let msg = subscriber.recv().unwrap();
         assert_eq!(i, *msg);
     }
 
@@ -52,7 +82,7 @@ mod tests {
 
         std::thread::sleep(std::time::Duration::from_secs(1));
         let i: usize = 2;
-        let msg = subscriber.recv();
+        let msg = subscriber.recv().unwrap();
         assert_eq!(i, *msg);
     }
 
@@ -75,12 +105,12 @@ mod tests {
         });
 
         let i: usize = 0;
-        let msg = subscriber1.recv();
+        let msg = subscriber1.recv().unwrap();
         assert_eq!(i, *msg);
 
         std::thread::sleep(std::time::Duration::from_secs(3));
         let i: usize = 2;
-        let msg = subscriber2.recv();
+        let msg = subscriber2.recv().unwrap();
         assert_eq!(i, *msg);
     }
 }
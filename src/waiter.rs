@@ -0,0 +1,376 @@
+use crate::event::EventEnvelope;
+use crossbeam::sync::{Parker, Unparker};
+use std::cell::UnsafeCell;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{Thread, ThreadId};
+
+#[cfg(feature = "async")]
+use futures::task::Waker;
+
+/// No reader is registered.
+const IDLE: u64 = 0;
+
+/// The registration is being changed by a reader, or by a publisher alerting it. Sequences never
+/// get this far, so it can't be mistaken for the sequence the readers wait for.
+const LOCKED: u64 = u64::MAX;
+
+/// A handle to wake up a reader that is waiting for an event to be published.
+#[derive(Clone)]
+pub(crate) enum Alerter {
+    Thread(Thread),
+
+    /// Unparks the thread that parks on the unparker's parker.
+    Unparker(Unparker, ThreadId),
+    #[cfg(feature = "async")]
+    Waker(Waker),
+}
+
+impl Alerter {
+    /// Creates an alerter that unparks the current thread from `parker`.
+    pub fn unparker(parker: &Parker) -> Self {
+        Alerter::Unparker(parker.unparker().clone(), std::thread::current().id())
+    }
+
+    /// Returns whether both alerters wake up the same reader.
+    fn wakes(&self, other: &Alerter) -> bool {
+        match (self, other) {
+            (Alerter::Thread(thread), Alerter::Thread(other)) => thread.id() == other.id(),
+            (Alerter::Unparker(_, thread), Alerter::Unparker(_, other)) => thread == other,
+            #[cfg(feature = "async")]
+            (Alerter::Waker(waker), Alerter::Waker(other)) => waker.will_wake(other),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    fn alert(self) {
+        match self {
+            Alerter::Thread(thread) => thread.unpark(),
+            Alerter::Unparker(unparker, _) => unparker.unpark(),
+            #[cfg(feature = "async")]
+            Alerter::Waker(waker) => waker.wake(),
+        }
+    }
+}
+
+/// The readers to alert, with the first kept inline so that a reader waiting on its own never
+/// allocates.
+#[derive(Default)]
+struct Alerters {
+    first: Option<Alerter>,
+    rest: Vec<Alerter>,
+}
+
+impl Alerters {
+    fn is_empty(&self) -> bool {
+        self.first.is_none()
+    }
+
+    fn insert(&mut self, alerter: Alerter) {
+        let mut registered = self.first.iter().chain(self.rest.iter());
+        if registered.any(|registered| registered.wakes(&alerter)) {
+            return;
+        }
+
+        match self.first {
+            Some(_) => self.rest.push(alerter),
+            None => self.first = Some(alerter),
+        }
+    }
+
+    fn remove(&mut self, alerter: &Alerter) {
+        match &self.first {
+            Some(first) if first.wakes(alerter) => self.first = self.rest.pop(),
+            _ => self.rest.retain(|registered| !registered.wakes(alerter)),
+        }
+    }
+
+    fn alert(self) {
+        for alerter in self.first.into_iter().chain(self.rest) {
+            alerter.alert();
+        }
+    }
+}
+
+/// The registration of the readers that are waiting for an event to be published, held by their
+/// [`GatingSequence`](crate::sequence::gating_sequence::GatingSequence).
+///
+/// Every reader that waits adds itself to the registration, which waits on the earliest sequence
+/// any of them waits for, and removes only itself once it stops waiting. Publishers alert, and
+/// clear, a registration waiting on a sequence up to the one they published.
+///
+/// Whoever changes the registration first swaps the waited-for sequence with `LOCKED`. Publishers
+/// never wait on that: a publisher that finds the registration locked records the sequence it
+/// published in `missed`, and whoever unlocks the registration alerts it if that sequence covers
+/// it. Readers spin while the registration is locked, which is only ever for as long as it takes
+/// to add or take out alerters.
+pub(crate) struct Waiter {
+    /// The sequence the readers are waiting to be published, `IDLE` while none are waiting, or
+    /// `LOCKED` while the registration is being changed.
+    waiting_for: AtomicU64,
+
+    /// The latest sequence published while the registration was locked.
+    missed: AtomicU64,
+
+    /// Only accessed by whoever moved `waiting_for` to `LOCKED`.
+    alerters: UnsafeCell<Alerters>,
+
+    /// The number of registrations on the ring that are waiting, so that publishers only look for
+    /// registrations when there are any.
+    waiting: Arc<AtomicU64>,
+}
+
+// The alerters are only accessed while holding the registration through `waiting_for`
+unsafe impl Send for Waiter {}
+unsafe impl Sync for Waiter {}
+
+impl Waiter {
+    pub fn new(waiting: Arc<AtomicU64>) -> Self {
+        Self {
+            waiting_for: AtomicU64::new(IDLE),
+            missed: AtomicU64::new(0),
+            alerters: UnsafeCell::new(Alerters::default()),
+            waiting,
+        }
+    }
+
+    /// Takes the registration from the reader's side, spinning while it is being changed.
+    /// Returns the sequence it was waiting for.
+    fn lock(&self) -> u64 {
+        loop {
+            match self.waiting_for.load(Ordering::Acquire) {
+                LOCKED => std::hint::spin_loop(),
+                waiting_for => {
+                    if self
+                        .waiting_for
+                        .compare_exchange_weak(
+                            waiting_for,
+                            LOCKED,
+                            Ordering::Acquire,
+                            Ordering::Relaxed,
+                        )
+                        .is_ok()
+                    {
+                        return waiting_for;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hands back the registration, waiting for `waiting_for`, and alerts it if a publisher
+    /// skipped it while it was locked.
+    fn unlock(&self, waiting_for: u64) {
+        self.waiting_for.store(waiting_for, Ordering::SeqCst);
+
+        if waiting_for == IDLE {
+            return;
+        }
+
+        // Pairs with the update of `missed` in `alert`, so that either the publisher sees the
+        // registration once it is unlocked, or the sequence it published is seen here
+        let missed = self.missed.swap(0, Ordering::SeqCst);
+        if missed >= waiting_for {
+            self.alert(missed);
+        }
+    }
+
+    /// Registers the reader to be alerted once `sequence` is published, alongside any other
+    /// readers that are waiting.
+    ///
+    /// The reader must check whether the sequence was published after registering, since a
+    /// publisher that got there first won't have seen the registration.
+    pub fn wait_for(&self, sequence: u64, alerter: Alerter) {
+        let waiting_for = match self.lock() {
+            IDLE => {
+                self.waiting.fetch_add(1, Ordering::SeqCst);
+                sequence
+            }
+            waiting_for => std::cmp::min(waiting_for, sequence),
+        };
+
+        unsafe { (*self.alerters.get()).insert(alerter) };
+        self.unlock(waiting_for);
+
+        // Pairs with the fence in `Sequencer::alert_waiters`, so that either the publisher sees
+        // the registration or the reader sees the published sequence
+        fence(Ordering::SeqCst);
+    }
+
+    /// Registers the reader to be alerted once the event at `sequence` is published into
+    /// `envelope`, and calls `park` unless it already was.
+    ///
+    /// Only the reader's own registration is removed afterwards, so readers sharing the waiter
+    /// between threads don't cancel each other.
+    pub fn wait(
+        &self,
+        envelope: &EventEnvelope,
+        sequence: u64,
+        alerter: Alerter,
+        park: impl FnOnce(),
+    ) {
+        self.wait_for(sequence, alerter.clone());
+
+        if envelope.sequence() < sequence {
+            park();
+        }
+
+        self.withdraw(&alerter);
+    }
+
+    /// Removes the registration of the reader that `alerter` wakes, if it wasn't alerted already.
+    fn withdraw(&self, alerter: &Alerter) {
+        if self.waiting_for.load(Ordering::Acquire) == IDLE {
+            return;
+        }
+
+        let waiting_for = self.lock();
+        let alerters = unsafe { &mut *self.alerters.get() };
+        alerters.remove(alerter);
+
+        if waiting_for != IDLE && alerters.is_empty() {
+            self.waiting.fetch_sub(1, Ordering::AcqRel);
+            self.unlock(IDLE);
+        } else {
+            self.unlock(waiting_for);
+        }
+    }
+
+    /// Removes the registrations of all readers, if they weren't alerted already.
+    ///
+    /// Only used by readers that aren't shared, such as asynchronous subscribers, which are polled
+    /// by a single task at a time.
+    pub fn cancel(&self) {
+        if self.waiting_for.load(Ordering::Acquire) == IDLE {
+            return;
+        }
+
+        if self.lock() != IDLE {
+            self.waiting.fetch_sub(1, Ordering::AcqRel);
+        }
+
+        let alerters = unsafe { std::mem::take(&mut *self.alerters.get()) };
+        self.unlock(IDLE);
+
+        drop(alerters);
+    }
+
+    /// Alerts the readers if they are waiting on a sequence up to `published`.
+    ///
+    /// Never waits: a registration that is locked is left to whoever unlocks it, after recording
+    /// the sequence it missed.
+    pub fn alert(&self, published: u64) {
+        loop {
+            let waiting_for = self.waiting_for.load(Ordering::SeqCst);
+
+            if waiting_for == LOCKED {
+                self.missed.fetch_max(published, Ordering::SeqCst);
+
+                // Unlocked before the missed sequence was recorded, so it is checked again here
+                if self.waiting_for.load(Ordering::SeqCst) == LOCKED {
+                    return;
+                }
+            } else if waiting_for == IDLE || waiting_for > published {
+                return;
+            } else if self
+                .waiting_for
+                .compare_exchange(waiting_for, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                let alerters = unsafe { std::mem::take(&mut *self.alerters.get()) };
+                self.waiting.fetch_sub(1, Ordering::AcqRel);
+                self.unlock(IDLE);
+
+                alerters.alert();
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        if *self.waiting_for.get_mut() != IDLE {
+            self.waiting.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::waiter::*;
+    use crossbeam::sync::Parker;
+
+    #[test]
+    fn alerts_waiters_up_to_published_sequence() {
+        let waiting = Arc::new(AtomicU64::new(0));
+        let waiter = Waiter::new(waiting.clone());
+        let parker = Parker::new();
+
+        waiter.wait_for(3, Alerter::unparker(&parker));
+        waiter.wait_for(2, Alerter::unparker(&parker));
+        assert_eq!(1, waiting.load(Ordering::Acquire));
+
+        // Publishing an earlier sequence leaves the registration in place
+        waiter.alert(1);
+        assert_eq!(1, waiting.load(Ordering::Acquire));
+
+        waiter.alert(2);
+        assert_eq!(0, waiting.load(Ordering::Acquire));
+        parker.park();
+
+        // A cancelled registration isn't alerted
+        waiter.wait_for(4, Alerter::unparker(&parker));
+        waiter.cancel();
+        waiter.alert(4);
+        assert_eq!(0, waiting.load(Ordering::Acquire));
+
+        waiter.wait_for(5, Alerter::Thread(std::thread::current()));
+        drop(waiter);
+        assert_eq!(0, waiting.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn keeps_registrations_of_other_readers() {
+        let waiting = Arc::new(AtomicU64::new(0));
+        let waiter = Arc::new(Waiter::new(waiting.clone()));
+        let parker = Parker::new();
+        waiter.wait_for(1, Alerter::unparker(&parker));
+
+        // An unparker from the same thread is the same reader, while one from another isn't
+        assert!(Alerter::unparker(&parker).wakes(&Alerter::unparker(&parker)));
+
+        let other = waiter.clone();
+        std::thread::spawn(move || {
+            let parker = Parker::new();
+            let alerter = Alerter::unparker(&parker);
+            other.wait_for(2, alerter.clone());
+            other.withdraw(&alerter);
+        })
+        .join()
+        .unwrap();
+
+        // The other thread only removed its own registration
+        assert_eq!(1, waiting.load(Ordering::Acquire));
+        waiter.alert(1);
+        parker.park();
+        assert_eq!(0, waiting.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn alerts_publishes_missed_while_locked() {
+        let waiting = Arc::new(AtomicU64::new(0));
+        let waiter = Waiter::new(waiting.clone());
+        let parker = Parker::new();
+        waiter.wait_for(2, Alerter::unparker(&parker));
+
+        // A publish that finds the registration locked is alerted once it is unlocked
+        let waiting_for = waiter.lock();
+        waiter.alert(2);
+        waiter.unlock(waiting_for);
+
+        assert_eq!(0, waiting.load(Ordering::Acquire));
+        parker.park();
+    }
+}
@@ -0,0 +1,509 @@
+use crate::error::Error;
+use crate::event::EventRead;
+use crate::subscriber::Subscriber;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How a windowed subscriber groups the events it receives.
+///
+/// Windows by count hold a number of consecutive events, while windows by duration hold the
+/// events that were published within a span of time. Time windows are aligned to the moment the
+/// subscriber was windowed, and windows that would be empty are skipped. A session window holds
+/// events until none has been published for an idle gap.
+///
+/// Events are windowed by the time they were published rather than received, so a subscriber
+/// catching up on a backlog still puts them into the windows they were published in. Windowing a
+/// subscriber makes publishers timestamp every event on the event-bus. Events published before
+/// then, or that arrive after their window already closed, join the open window.
+///
+/// Windows follow the order of the sequences in the ring. When a subscriber lags and skips over
+/// events that were overwritten, no window spans the skipped events: a window by count that was
+/// open is dropped, and counting starts over from the next event, while a window by time is
+/// closed early with the events it holds.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(1024)?;
+///
+/// // the last 10 prices, every 5 prices
+/// let mut prices = eventbus.subscribe::<Price>().window(Window::sliding_count(10, 5));
+/// let prices = prices.recv()?;
+/// ```
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Window {
+    kind: WindowKind,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum WindowKind {
+    Count { size: usize, step: usize },
+    Duration { size: Duration, step: Duration },
+    Session { gap: Duration },
+}
+
+impl Window {
+    /// Windows of `size` consecutive events, which don't overlap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    pub fn tumbling_count(size: usize) -> Self {
+        Self::sliding_count(size, size)
+    }
+
+    /// Windows of `size` consecutive events, which start every `step` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` or `step` is zero.
+    ///
+    pub fn sliding_count(size: usize, step: usize) -> Self {
+        assert!(
+            size > 0 && step > 0,
+            "window size and step must not be zero"
+        );
+
+        Self {
+            kind: WindowKind::Count { size, step },
+        }
+    }
+
+    /// Windows of the events published within `size`, which don't overlap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    pub fn tumbling(size: Duration) -> Self {
+        Self::sliding(size, size)
+    }
+
+    /// Windows of the events published within `size`, which start every `step`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` or `step` is zero.
+    ///
+    pub fn sliding(size: Duration, step: Duration) -> Self {
+        assert!(
+            !size.is_zero() && !step.is_zero(),
+            "window size and step must not be zero"
+        );
+
+        Self {
+            kind: WindowKind::Duration { size, step },
+        }
+    }
+
+    /// Windows of events that were published less than `gap` apart.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `gap` is zero.
+    ///
+    pub fn session(gap: Duration) -> Self {
+        assert!(!gap.is_zero(), "session gap must not be zero");
+
+        Self {
+            kind: WindowKind::Session { gap },
+        }
+    }
+}
+
+/// Groups received events into windows, independently of how the events are received.
+pub(crate) struct Windower<T> {
+    kind: WindowKind,
+    events: VecDeque<(Instant, EventRead<T>)>,
+    ready: VecDeque<Vec<EventRead<T>>>,
+    origin: Instant,
+
+    /// The number of events to skip before the next window, when windows start further apart than
+    /// their size.
+    skip: usize,
+
+    /// The index of the next time window to close, counted from the origin.
+    index: u64,
+}
+
+impl<T> Windower<T> {
+    pub(crate) fn new(window: Window) -> Self {
+        Self {
+            kind: window.kind,
+            events: VecDeque::new(),
+            ready: VecDeque::new(),
+            origin: Instant::now(),
+            skip: 0,
+            index: 0,
+        }
+    }
+
+    /// Takes the oldest window that was closed.
+    pub(crate) fn pop(&mut self) -> Option<Vec<EventRead<T>>> {
+        self.ready.pop_front()
+    }
+
+    /// Adds an event that was published at `at`, and whether the subscriber skipped over any events
+    /// right before it.
+    pub(crate) fn push(&mut self, at: Instant, event: EventRead<T>, lagged: bool) {
+        if lagged {
+            match self.kind {
+                WindowKind::Count { .. } => {
+                    self.events.clear();
+                    self.skip = 0;
+                }
+                _ => self.flush(),
+            }
+        }
+
+        // Time windows keep their events in order, so events that were published out of order
+        // with the ones before them, or into a window that already closed, join the open window
+        let at = match self.kind {
+            WindowKind::Duration { .. } => at.max(self.window_start(self.index)),
+            _ => at,
+        };
+        let at = self.events.back().map_or(at, |(last, _)| at.max(*last));
+
+        match self.kind {
+            WindowKind::Count { size, step } => {
+                if self.skip > 0 {
+                    self.skip -= 1;
+                    return;
+                }
+
+                self.events.push_back((at, event));
+
+                if self.events.len() == size {
+                    let window = self.events.iter().map(|(_, e)| e.duplicate()).collect();
+                    self.ready.push_back(window);
+
+                    self.events.drain(..step.min(size));
+                    self.skip = step.saturating_sub(size);
+                }
+            }
+
+            WindowKind::Duration { .. } => {
+                self.expire(at);
+                self.events.push_back((at, event));
+            }
+
+            WindowKind::Session { gap } => {
+                if self
+                    .events
+                    .back()
+                    .is_some_and(|(last, _)| at >= *last + gap)
+                {
+                    self.flush();
+                }
+
+                self.events.push_back((at, event));
+            }
+        }
+    }
+
+    /// Get when the open window closes by time, if it does.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        match self.kind {
+            WindowKind::Count { .. } => None,
+
+            WindowKind::Duration { size, .. } if !self.events.is_empty() => {
+                Some(self.window_start(self.index) + size)
+            }
+            WindowKind::Duration { .. } => None,
+
+            WindowKind::Session { gap } => self.events.back().map(|(last, _)| *last + gap),
+        }
+    }
+
+    /// Closes every window that ended by `now`.
+    pub(crate) fn expire(&mut self, now: Instant) {
+        match self.kind {
+            WindowKind::Count { .. } => {}
+
+            WindowKind::Duration { size, step } => loop {
+                if self.events.is_empty() {
+                    // Skip the windows that ended without any events
+                    self.index = std::cmp::max(self.index, self.first_open_window(now, size, step));
+                    break;
+                }
+
+                let start = self.window_start(self.index);
+                let end = start + size;
+                if end > now {
+                    break;
+                }
+
+                let window: Vec<_> = self
+                    .events
+                    .iter()
+                    .filter(|(at, _)| *at >= start && *at < end)
+                    .map(|(_, event)| event.duplicate())
+                    .collect();
+
+                if !window.is_empty() {
+                    self.ready.push_back(window);
+                }
+
+                self.index += 1;
+
+                let next_start = self.window_start(self.index);
+                while self.events.front().is_some_and(|(at, _)| *at < next_start) {
+                    self.events.pop_front();
+                }
+            },
+
+            WindowKind::Session { .. } => {
+                if self.deadline().is_some_and(|deadline| now >= deadline) {
+                    self.flush();
+                }
+            }
+        }
+    }
+
+    /// Closes the open window early, with whatever events it holds.
+    pub(crate) fn flush(&mut self) {
+        if !self.events.is_empty() {
+            let window = self.events.drain(..).map(|(_, event)| event).collect();
+            self.ready.push_back(window);
+        }
+    }
+
+    fn window_start(&self, index: u64) -> Instant {
+        let step = match self.kind {
+            WindowKind::Duration { step, .. } => step,
+            _ => Duration::ZERO,
+        };
+
+        self.origin + Duration::from_nanos((step.as_nanos() * index as u128) as u64)
+    }
+
+    /// Get the index of the first window that ends after `now`.
+    fn first_open_window(&self, now: Instant, size: Duration, step: Duration) -> u64 {
+        let elapsed = now.saturating_duration_since(self.origin).as_nanos();
+
+        match elapsed.checked_sub(size.as_nanos()) {
+            Some(ended) => (ended / step.as_nanos()) as u64 + 1,
+            None => 0,
+        }
+    }
+}
+
+/// A [`Subscriber`] that receives events in windows, created by [`Subscriber::window`].
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```ignore
+/// let eventbus = Eventador::new(1024)?;
+/// let mut subscriber = eventbus
+///     .subscribe::<usize>()
+///     .window(Window::tumbling(Duration::from_secs(1)));
+///
+/// let per_second: usize = subscriber.recv_fold(0, |sum, i| sum + i)?;
+/// ```
+///
+pub struct WindowedSubscriber<T: 'static> {
+    subscriber: Subscriber<T>,
+    windower: Windower<T>,
+}
+
+impl<T: 'static + Send> WindowedSubscriber<T> {
+    pub(crate) fn new(subscriber: Subscriber<T>, window: Window) -> Self {
+        Self {
+            subscriber,
+            windower: Windower::new(window),
+        }
+    }
+
+    /// Synchronously read the next window of events from the event-bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the subscriber was evicted from the event-bus for lagging.
+    ///
+    pub fn recv(&mut self) -> Result<Vec<EventRead<T>>, Error> {
+        loop {
+            if let Some(window) = self.windower.pop() {
+                return Ok(window);
+            }
+
+            let lagged = self.subscriber.lagged();
+            let deadline = self.windower.deadline();
+
+            match self.subscriber.recv_until(deadline)? {
+                Some(event) => {
+                    let lagged = self.subscriber.lagged() != lagged;
+                    let at = self.subscriber.published_at().unwrap_or_else(Instant::now);
+                    self.windower.push(at, event, lagged);
+                }
+                None => self.windower.expire(Instant::now()),
+            }
+        }
+    }
+
+    /// Synchronously read the next window of events from the event-bus, and aggregate it with `f`
+    /// starting from `init`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Evicted`] if the subscriber was evicted from the event-bus for lagging.
+    ///
+    pub fn recv_fold<A>(&mut self, init: A, f: impl FnMut(A, &T) -> A) -> Result<A, Error> {
+        let window = self.recv()?;
+        Ok(window.iter().map(|event| &**event).fold(init, f))
+    }
+
+    /// Get the [`Subscriber`] that the windows are received from.
+    pub fn subscriber(&self) -> &Subscriber<T> {
+        &self.subscriber
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::window::Windower;
+    use crate::{Eventador, StartPosition, WaitStrategy, Window};
+    use std::time::{Duration, Instant};
+
+    fn values(window: Vec<crate::EventRead<usize>>) -> Vec<usize> {
+        window.iter().map(|event| **event).collect()
+    }
+
+    #[test]
+    fn windows_by_count() {
+        let eventbus = Eventador::new(16).unwrap();
+        let mut tumbling = eventbus
+            .subscribe::<usize>()
+            .window(Window::tumbling_count(2));
+        let mut sliding = eventbus
+            .subscribe::<usize>()
+            .window(Window::sliding_count(3, 1));
+
+        for i in 1..=5_usize {
+            eventbus.publish(i);
+        }
+
+        assert_eq!(vec![1, 2], values(tumbling.recv().unwrap()));
+        assert_eq!(7, tumbling.recv_fold(0, |sum, i| sum + i).unwrap());
+
+        assert_eq!(vec![1, 2, 3], values(sliding.recv().unwrap()));
+        assert_eq!(vec![2, 3, 4], values(sliding.recv().unwrap()));
+        assert_eq!(vec![3, 4, 5], values(sliding.recv().unwrap()));
+
+        let mut hopping = eventbus
+            .subscribe_from::<usize>(StartPosition::Earliest)
+            .window(Window::sliding_count(2, 3));
+        assert_eq!(vec![1, 2], values(hopping.recv().unwrap()));
+        assert_eq!(vec![4, 5], values(hopping.recv().unwrap()));
+    }
+
+    #[test]
+    fn windows_by_duration() {
+        let eventbus = Eventador::new(16).unwrap();
+        let mut subscriber = eventbus
+            .subscribe::<usize>()
+            .window(Window::tumbling(Duration::from_millis(50)));
+
+        let started = Instant::now();
+        eventbus.publish(1_usize);
+        eventbus.publish(2_usize);
+
+        assert_eq!(vec![1, 2], values(subscriber.recv().unwrap()));
+        assert!(started.elapsed() >= Duration::from_millis(40));
+
+        // Empty windows are skipped
+        std::thread::sleep(Duration::from_millis(120));
+        eventbus.publish(3_usize);
+        assert_eq!(vec![3], values(subscriber.recv().unwrap()));
+    }
+
+    #[test]
+    fn windows_by_session() {
+        let eventbus = Eventador::new(16).unwrap();
+        let mut subscriber = eventbus
+            .subscribe::<usize>()
+            .window(Window::session(Duration::from_millis(30)));
+
+        let publish_bus = eventbus.clone();
+        let publish_thread = std::thread::spawn(move || {
+            publish_bus.publish(1_usize);
+            publish_bus.publish(2_usize);
+
+            std::thread::sleep(Duration::from_millis(100));
+            publish_bus.publish(3_usize);
+        });
+
+        assert_eq!(vec![1, 2], values(subscriber.recv().unwrap()));
+        assert_eq!(vec![3], values(subscriber.recv().unwrap()));
+
+        publish_thread.join().unwrap();
+    }
+
+    #[test]
+    fn windows_backlog_by_publish_time() {
+        let eventbus = Eventador::new(16).unwrap();
+        let mut subscriber = eventbus
+            .subscribe::<usize>()
+            .window(Window::tumbling(Duration::from_millis(50)));
+
+        eventbus.publish(1_usize);
+        eventbus.publish(2_usize);
+        std::thread::sleep(Duration::from_millis(120));
+        eventbus.publish(3_usize);
+
+        // All three events are read at once, but were published in different windows
+        assert_eq!(vec![1, 2], values(subscriber.recv().unwrap()));
+        assert_eq!(vec![3], values(subscriber.recv().unwrap()));
+    }
+
+    #[test]
+    fn closes_windows_on_lag() {
+        let eventbus = Eventador::with_strategy(4, WaitStrategy::NoWait).unwrap();
+        let subscriber = eventbus.subscribe::<usize>();
+        let mut tumbling = Windower::new(Window::tumbling_count(3));
+        let mut sliding = Windower::new(Window::sliding_count(3, 1));
+        let mut hopping = Windower::new(Window::sliding_count(2, 3));
+
+        let mut push = |event: crate::EventRead<usize>, lagged: bool| {
+            tumbling.push(Instant::now(), event.duplicate(), lagged);
+            sliding.push(Instant::now(), event.duplicate(), lagged);
+            hopping.push(Instant::now(), event, lagged);
+        };
+
+        for i in 1..=3_usize {
+            eventbus.publish(i);
+        }
+
+        push(subscriber.recv().unwrap(), false);
+        push(subscriber.recv().unwrap(), false);
+
+        // Events 3 to 6 are overwritten before they can be read
+        for i in 4..=10_usize {
+            eventbus.publish(i);
+        }
+
+        let lagged = subscriber.lagged();
+        let event = subscriber.recv().unwrap();
+        assert_eq!(7, *event);
+        push(event, subscriber.lagged() != lagged);
+
+        push(subscriber.recv().unwrap(), false);
+        push(subscriber.recv().unwrap(), false);
+
+        // The partial windows are dropped, and counting starts over after the skipped events
+        assert_eq!(vec![7, 8, 9], values(tumbling.pop().unwrap()));
+        assert!(tumbling.pop().is_none());
+
+        assert_eq!(vec![7, 8, 9], values(sliding.pop().unwrap()));
+        assert!(sliding.pop().is_none());
+
+        assert_eq!(vec![1, 2], values(hopping.pop().unwrap()));
+        assert_eq!(vec![7, 8], values(hopping.pop().unwrap()));
+        assert!(hopping.pop().is_none());
+    }
+}